@@ -1,11 +1,198 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
+export interface Radiation {
+  intended?: string
+  kAlpha1?: number
+  kAlpha2?: number
+  kBeta?: number
+  ratioKAlpha2KAlpha1?: number
+  unit?: string
+}
+
+export interface Scan {
+  index?: number
+  axis?: string
+  xAxis?: string
+  mode?: string
+  status?: string
+  start?: number
+  end?: number
+  step?: number
+  speed?: number
+  speedUnit?: string
+  positionUnit?: string
+  intensityUnit?: string
+}
+
+export interface XrayGenerator {
+  target?: string
+  voltage?: number
+  current?: number
+  focus?: string
+}
+
+export interface AxisSetting {
+  name: string
+  unit?: string
+  position?: string
+  offset?: number
+  state?: string
+}
+
+export interface MeasurementConditions {
+  generator?: XrayGenerator
+  detector?: string
+  optics?: string
+  geometry?: string
+  hardware: Record<string, string>
+  axes: Array<AxisSetting>
+}
+
+export interface BankHeader {
+  number: number
+  channels: number
+  records: number
+  binning: string
+  coefficients: Array<number>
+  dataType: string
+}
+
+export interface Metadata {
+  title?: string
+  sampleName?: string
+  operator?: string
+  instrument?: string
+  anode?: string
+  wavelength?: number
+  radiation?: Radiation
+  countingTime?: number
+  goniometerRadius?: number
+  scan?: Scan
+  conditions?: MeasurementConditions
+  bank?: BankHeader
+  startTime?: string
+  endTime?: string
+  extra: Record<string, string>
+}
+
 export interface Pattern {
   x: Array<number>
   y: Array<number>
   e?: Array<number>
+  attenuation?: Array<number>
+  metadata: Metadata
+}
+
+export declare const enum WarningKind {
+  SkippedLine = 'SkippedLine',
+  InconsistentColumns = 'InconsistentColumns',
+  InvalidValue = 'InvalidValue',
+  ByteOrderMark = 'ByteOrderMark',
+  InvalidMetadata = 'InvalidMetadata',
+  Other = 'Other'
+}
+
+export interface Location {
+  line: number
+  offset: number
+  text: string
 }
 
-export declare function read(path: string): Pattern
+export interface Warning {
+  kind: WarningKind
+  message: string
+  count: number
+  locations: Array<Location>
+}
+
+export interface ReadReport {
+  pattern: Pattern
+  warnings: Array<Warning>
+}
+
+export interface ReciprocalSpaceMap {
+  scanAxis: string
+  stepAxis: string
+  stepPositions: Array<number>
+  omega: Array<Array<number>>
+  twoTheta: Array<Array<number>>
+  intensities: Array<Array<number>>
+  attenuation?: Array<Array<number>>
+  metadata: Metadata
+}
+
+export interface ReciprocalSpaceQ {
+  qx: Array<Array<number>>
+  qz: Array<Array<number>>
+}
+
+export interface BrukerCandidate {
+  layout: string
+  count: number
+  stride: number
+  dataOffset: number
+  valueOffset: number
+  startOffset?: number
+  stepOffset?: number
+  start?: number
+  step?: number
+  score?: number
+  reason?: string
+}
+
+export interface BrukerRawDiagnostics {
+  version?: string
+  heuristic: boolean
+  selected?: BrukerCandidate
+  rejected: Array<BrukerCandidate>
+  confidence: number
+}
+
+export declare const enum Format {
+  GsasRaw = 'GsasRaw',
+  BrukerRaw = 'BrukerRaw',
+  Rasx = 'Rasx',
+  Xrdml = 'Xrdml',
+  Xy = 'Xy',
+  Csv = 'Csv'
+}
+
+export interface ReadOptions {
+  applyAttenuation?: boolean
+  gsasCentidegrees?: boolean
+  rasxDataSet?: number
+  xrdmlAxis?: string
+  xrdmlCountsPerSecond?: boolean
+  commentPrefixes?: Array<string>
+  strict?: boolean
+}
+
+export declare function read(path: string, options?: ReadOptions | undefined | null): Pattern
+
+export declare function readBytes(data: Buffer, filename: string, options?: ReadOptions | undefined | null): Pattern
+
+export declare function readReport(path: string, options?: ReadOptions | undefined | null): ReadReport
+
+export declare function readBytesReport(data: Buffer, filename: string, options?: ReadOptions | undefined | null): ReadReport
+
+export declare function readWithFormat(path: string, format: Format, options?: ReadOptions | undefined | null): Pattern
+
+export declare function readBytesWithFormat(data: Buffer, format: Format, options?: ReadOptions | undefined | null): Pattern
+
+export declare function readAll(path: string, options?: ReadOptions | undefined | null): Array<Pattern>
+
+export declare function readAllBytes(data: Buffer, filename: string, options?: ReadOptions | undefined | null): Array<Pattern>
+
+export declare function readXrdmlMap(path: string, options?: ReadOptions | undefined | null): ReciprocalSpaceMap
+
+export declare function readXrdmlMapBytes(data: Buffer, options?: ReadOptions | undefined | null): ReciprocalSpaceMap
+
+/**
+ * Converts every point of `map` to `(qx, qz)` in inverse angstrom, using
+ * `wavelength` or else the wavelength in the map metadata.
+ */
+export declare function xrdmlMapToQ(map: ReciprocalSpaceMap, wavelength?: number | undefined | null): ReciprocalSpaceQ
+
+export declare function diagnoseBrukerRaw(path: string): BrukerRawDiagnostics
 
-export declare function readBytes(data: Buffer, filename: string): Pattern
+export declare function diagnoseBrukerRawBytes(data: Buffer): BrukerRawDiagnostics
@@ -1,13 +1,204 @@
 #![deny(clippy::all)]
 
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Cursor;
+
 use napi::bindgen_prelude::Buffer;
 use napi_derive::napi;
 
+#[napi(object)]
+pub struct Radiation {
+    pub intended: Option<String>,
+    pub k_alpha1: Option<f64>,
+    pub k_alpha2: Option<f64>,
+    pub k_beta: Option<f64>,
+    pub ratio_k_alpha2_k_alpha1: Option<f64>,
+    pub unit: Option<String>,
+}
+
+impl From<geddes::Radiation> for Radiation {
+    fn from(value: geddes::Radiation) -> Self {
+        Self {
+            intended: value.intended,
+            k_alpha1: value.k_alpha1,
+            k_alpha2: value.k_alpha2,
+            k_beta: value.k_beta,
+            ratio_k_alpha2_k_alpha1: value.ratio_k_alpha2_k_alpha1,
+            unit: value.unit,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct Scan {
+    pub index: Option<u32>,
+    pub axis: Option<String>,
+    pub x_axis: Option<String>,
+    pub mode: Option<String>,
+    pub status: Option<String>,
+    pub start: Option<f64>,
+    pub end: Option<f64>,
+    pub step: Option<f64>,
+    pub speed: Option<f64>,
+    pub speed_unit: Option<String>,
+    pub position_unit: Option<String>,
+    pub intensity_unit: Option<String>,
+}
+
+impl From<geddes::Scan> for Scan {
+    fn from(value: geddes::Scan) -> Self {
+        Self {
+            index: value.index,
+            axis: value.axis,
+            x_axis: value.x_axis,
+            mode: value.mode,
+            status: value.status,
+            start: value.start,
+            end: value.end,
+            step: value.step,
+            speed: value.speed,
+            speed_unit: value.speed_unit,
+            position_unit: value.position_unit,
+            intensity_unit: value.intensity_unit,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct XrayGenerator {
+    pub target: Option<String>,
+    pub voltage: Option<f64>,
+    pub current: Option<f64>,
+    pub focus: Option<String>,
+}
+
+impl From<geddes::XrayGenerator> for XrayGenerator {
+    fn from(value: geddes::XrayGenerator) -> Self {
+        Self {
+            target: value.target,
+            voltage: value.voltage,
+            current: value.current,
+            focus: value.focus,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct AxisSetting {
+    pub name: String,
+    pub unit: Option<String>,
+    pub position: Option<String>,
+    pub offset: Option<f64>,
+    pub state: Option<String>,
+}
+
+impl From<geddes::AxisSetting> for AxisSetting {
+    fn from(value: geddes::AxisSetting) -> Self {
+        Self {
+            name: value.name,
+            unit: value.unit,
+            position: value.position,
+            offset: value.offset,
+            state: value.state,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct MeasurementConditions {
+    pub generator: Option<XrayGenerator>,
+    pub detector: Option<String>,
+    pub optics: Option<String>,
+    pub geometry: Option<String>,
+    pub hardware: HashMap<String, String>,
+    pub axes: Vec<AxisSetting>,
+}
+
+impl From<geddes::MeasurementConditions> for MeasurementConditions {
+    fn from(value: geddes::MeasurementConditions) -> Self {
+        Self {
+            generator: value.generator.map(Into::into),
+            detector: value.detector,
+            optics: value.optics,
+            geometry: value.geometry,
+            hardware: value.hardware.into_iter().collect(),
+            axes: value.axes.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct BankHeader {
+    pub number: u32,
+    pub channels: u32,
+    pub records: u32,
+    pub binning: String,
+    pub coefficients: Vec<f64>,
+    pub data_type: String,
+}
+
+impl From<geddes::BankHeader> for BankHeader {
+    fn from(value: geddes::BankHeader) -> Self {
+        Self {
+            number: value.number,
+            channels: value.channels as u32,
+            records: value.records as u32,
+            binning: value.binning,
+            coefficients: value.coefficients,
+            data_type: value.data_type,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub sample_name: Option<String>,
+    pub operator: Option<String>,
+    pub instrument: Option<String>,
+    pub anode: Option<String>,
+    pub wavelength: Option<f64>,
+    pub radiation: Option<Radiation>,
+    pub counting_time: Option<f64>,
+    pub goniometer_radius: Option<f64>,
+    pub scan: Option<Scan>,
+    pub conditions: Option<MeasurementConditions>,
+    pub bank: Option<BankHeader>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub extra: HashMap<String, String>,
+}
+
+impl From<geddes::Metadata> for Metadata {
+    fn from(value: geddes::Metadata) -> Self {
+        Self {
+            title: value.title,
+            sample_name: value.sample_name,
+            operator: value.operator,
+            instrument: value.instrument,
+            anode: value.anode,
+            wavelength: value.wavelength,
+            radiation: value.radiation.map(Into::into),
+            counting_time: value.counting_time,
+            goniometer_radius: value.goniometer_radius,
+            scan: value.scan.map(Into::into),
+            conditions: value.conditions.map(Into::into),
+            bank: value.bank.map(Into::into),
+            start_time: value.start_time,
+            end_time: value.end_time,
+            extra: value.extra.into_iter().collect(),
+        }
+    }
+}
+
 #[napi(object)]
 pub struct Pattern {
     pub x: Vec<f64>,
     pub y: Vec<f64>,
     pub e: Option<Vec<f64>>,
+    pub attenuation: Option<Vec<f64>>,
+    pub metadata: Metadata,
 }
 
 impl From<geddes::Pattern> for Pattern {
@@ -16,22 +207,387 @@ impl From<geddes::Pattern> for Pattern {
             x: value.x,
             y: value.y,
             e: value.e,
+            attenuation: value.attenuation,
+            metadata: value.metadata.into(),
+        }
+    }
+}
+
+#[napi(string_enum)]
+pub enum WarningKind {
+    SkippedLine,
+    InconsistentColumns,
+    InvalidValue,
+    ByteOrderMark,
+    InvalidMetadata,
+    Other,
+}
+
+impl From<geddes::WarningKind> for WarningKind {
+    fn from(value: geddes::WarningKind) -> Self {
+        match value {
+            geddes::WarningKind::SkippedLine => Self::SkippedLine,
+            geddes::WarningKind::InconsistentColumns => Self::InconsistentColumns,
+            geddes::WarningKind::InvalidValue => Self::InvalidValue,
+            geddes::WarningKind::ByteOrderMark => Self::ByteOrderMark,
+            geddes::WarningKind::InvalidMetadata => Self::InvalidMetadata,
+            // Kinds added to the parser before this binding knows about them.
+            _ => Self::Other,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct Location {
+    pub line: i64,
+    pub offset: i64,
+    pub text: String,
+}
+
+impl From<geddes::Location> for Location {
+    fn from(value: geddes::Location) -> Self {
+        Self {
+            line: to_i64(value.line),
+            offset: to_i64(value.offset),
+            text: value.text,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+    pub count: i64,
+    pub locations: Vec<Location>,
+}
+
+impl From<geddes::Warning> for Warning {
+    fn from(value: geddes::Warning) -> Self {
+        Self {
+            kind: value.kind.into(),
+            message: value.message,
+            count: to_i64(value.count),
+            locations: value.locations.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct ReadReport {
+    pub pattern: Pattern,
+    pub warnings: Vec<Warning>,
+}
+
+impl From<geddes::ReadReport> for ReadReport {
+    fn from(value: geddes::ReadReport) -> Self {
+        Self {
+            pattern: value.pattern.into(),
+            warnings: value.warnings.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct ReciprocalSpaceMap {
+    pub scan_axis: String,
+    pub step_axis: String,
+    pub step_positions: Vec<f64>,
+    pub omega: Vec<Vec<f64>>,
+    pub two_theta: Vec<Vec<f64>>,
+    pub intensities: Vec<Vec<f64>>,
+    pub attenuation: Option<Vec<Vec<f64>>>,
+    pub metadata: Metadata,
+}
+
+impl From<geddes::ReciprocalSpaceMap> for ReciprocalSpaceMap {
+    fn from(value: geddes::ReciprocalSpaceMap) -> Self {
+        Self {
+            scan_axis: value.scan_axis,
+            step_axis: value.step_axis,
+            step_positions: value.step_positions,
+            omega: value.omega,
+            two_theta: value.two_theta,
+            intensities: value.intensities,
+            attenuation: value.attenuation,
+            metadata: value.metadata.into(),
         }
     }
 }
 
+#[napi(object)]
+pub struct ReciprocalSpaceQ {
+    pub qx: Vec<Vec<f64>>,
+    pub qz: Vec<Vec<f64>>,
+}
+
 fn to_napi_error(err: geddes::Error) -> napi::Error {
     napi::Error::from_reason(err.to_string())
 }
 
+/// Converts a line number, offset or count; values past `i64::MAX` saturate.
+fn to_i64(value: impl TryInto<i64>) -> i64 {
+    value.try_into().unwrap_or(i64::MAX)
+}
+
+#[napi(object)]
+pub struct BrukerCandidate {
+    pub layout: String,
+    pub count: u32,
+    pub stride: u32,
+    pub data_offset: u32,
+    pub value_offset: u32,
+    pub start_offset: Option<u32>,
+    pub step_offset: Option<u32>,
+    pub start: Option<f64>,
+    pub step: Option<f64>,
+    pub score: Option<f64>,
+    pub reason: Option<String>,
+}
+
+impl From<geddes::BrukerCandidate> for BrukerCandidate {
+    fn from(value: geddes::BrukerCandidate) -> Self {
+        Self {
+            layout: value.layout,
+            count: value.count as u32,
+            stride: value.stride as u32,
+            data_offset: value.data_offset as u32,
+            value_offset: value.value_offset as u32,
+            start_offset: value.start_offset.map(|v| v as u32),
+            step_offset: value.step_offset.map(|v| v as u32),
+            start: value.start,
+            step: value.step,
+            score: value.score,
+            reason: value.reason,
+        }
+    }
+}
+
+#[napi(object)]
+pub struct BrukerRawDiagnostics {
+    pub version: Option<String>,
+    pub heuristic: bool,
+    pub selected: Option<BrukerCandidate>,
+    pub rejected: Vec<BrukerCandidate>,
+    pub confidence: f64,
+}
+
+impl From<geddes::BrukerRawDiagnostics> for BrukerRawDiagnostics {
+    fn from(value: geddes::BrukerRawDiagnostics) -> Self {
+        Self {
+            version: value.version,
+            heuristic: value.heuristic,
+            selected: value.selected.map(Into::into),
+            rejected: value.rejected.into_iter().map(Into::into).collect(),
+            confidence: value.confidence,
+        }
+    }
+}
+
+#[napi(string_enum)]
+pub enum Format {
+    GsasRaw,
+    BrukerRaw,
+    Rasx,
+    Xrdml,
+    Xy,
+    Csv,
+}
+
+impl From<Format> for geddes::Format {
+    fn from(value: Format) -> Self {
+        match value {
+            Format::GsasRaw => Self::GsasRaw,
+            Format::BrukerRaw => Self::BrukerRaw,
+            Format::Rasx => Self::Rasx,
+            Format::Xrdml => Self::Xrdml,
+            Format::Xy => Self::Xy,
+            Format::Csv => Self::Csv,
+        }
+    }
+}
+
+#[napi(object)]
+#[derive(Default)]
+pub struct ReadOptions {
+    pub apply_attenuation: Option<bool>,
+    pub gsas_centidegrees: Option<bool>,
+    pub rasx_data_set: Option<u32>,
+    pub xrdml_axis: Option<String>,
+    pub xrdml_counts_per_second: Option<bool>,
+    pub comment_prefixes: Option<Vec<String>>,
+    pub strict: Option<bool>,
+}
+
+impl From<ReadOptions> for geddes::ReadOptions {
+    fn from(value: ReadOptions) -> Self {
+        let mut options = geddes::ReadOptions::new();
+        if let Some(apply) = value.apply_attenuation {
+            options = options.apply_attenuation(apply);
+        }
+        if let Some(centidegrees) = value.gsas_centidegrees {
+            options = options.gsas_centidegrees(centidegrees);
+        }
+        if let Some(index) = value.rasx_data_set {
+            options = options.rasx_data_set(index as usize);
+        }
+        if let Some(axis) = value.xrdml_axis {
+            options = options.xrdml_axis(axis);
+        }
+        if let Some(cps) = value.xrdml_counts_per_second {
+            options = options.xrdml_counts_per_second(cps);
+        }
+        if let Some(prefixes) = value.comment_prefixes {
+            options = options.comment_prefixes(prefixes);
+        }
+        if let Some(strict) = value.strict {
+            options = options.strict(strict);
+        }
+        options
+    }
+}
+
+#[napi]
+pub fn read(path: String, options: Option<ReadOptions>) -> napi::Result<Pattern> {
+    let options = options.unwrap_or_default().into();
+    geddes::read_with(path, &options)
+        .map(Into::into)
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn read_bytes(
+    data: Buffer,
+    filename: String,
+    options: Option<ReadOptions>,
+) -> napi::Result<Pattern> {
+    let options = options.unwrap_or_default().into();
+    geddes::read_bytes_with(data.as_ref(), &filename, &options)
+        .map(Into::into)
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn read_report(path: String, options: Option<ReadOptions>) -> napi::Result<ReadReport> {
+    let options = options.unwrap_or_default().into();
+    geddes::read_report(path, &options)
+        .map(Into::into)
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn read_bytes_report(
+    data: Buffer,
+    filename: String,
+    options: Option<ReadOptions>,
+) -> napi::Result<ReadReport> {
+    let options = options.unwrap_or_default().into();
+    geddes::read_report_bytes(data.as_ref(), &filename, &options)
+        .map(Into::into)
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn read_with_format(
+    path: String,
+    format: Format,
+    options: Option<ReadOptions>,
+) -> napi::Result<Pattern> {
+    let options = options.unwrap_or_default().into();
+    let file = File::open(path).map_err(|err| to_napi_error(err.into()))?;
+    geddes::read_with_format(file, format.into(), &options)
+        .map(Into::into)
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn read_bytes_with_format(
+    data: Buffer,
+    format: Format,
+    options: Option<ReadOptions>,
+) -> napi::Result<Pattern> {
+    let options = options.unwrap_or_default().into();
+    geddes::read_with_format(Cursor::new(data.as_ref()), format.into(), &options)
+        .map(Into::into)
+        .map_err(to_napi_error)
+}
+
 #[napi]
-pub fn read(path: String) -> napi::Result<Pattern> {
-    geddes::read(path).map(Into::into).map_err(to_napi_error)
+pub fn read_all(path: String, options: Option<ReadOptions>) -> napi::Result<Vec<Pattern>> {
+    let options = options.unwrap_or_default().into();
+    geddes::read_all_with(path, &options)
+        .map(|patterns| patterns.into_iter().map(Into::into).collect())
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn read_all_bytes(
+    data: Buffer,
+    filename: String,
+    options: Option<ReadOptions>,
+) -> napi::Result<Vec<Pattern>> {
+    let options = options.unwrap_or_default().into();
+    geddes::read_all_bytes_with(data.as_ref(), &filename, &options)
+        .map(|patterns| patterns.into_iter().map(Into::into).collect())
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn read_xrdml_map(
+    path: String,
+    options: Option<ReadOptions>,
+) -> napi::Result<ReciprocalSpaceMap> {
+    let options = options.unwrap_or_default().into();
+    geddes::read_xrdml_map(path, &options)
+        .map(Into::into)
+        .map_err(to_napi_error)
+}
+
+#[napi]
+pub fn read_xrdml_map_bytes(
+    data: Buffer,
+    options: Option<ReadOptions>,
+) -> napi::Result<ReciprocalSpaceMap> {
+    let options = options.unwrap_or_default().into();
+    geddes::read_xrdml_map_bytes(data.as_ref(), &options)
+        .map(Into::into)
+        .map_err(to_napi_error)
+}
+
+/// Converts every point of `map` to `(qx, qz)` in inverse angstrom, using
+/// `wavelength` or else the wavelength in the map metadata.
+#[napi]
+pub fn xrdml_map_to_q(
+    map: ReciprocalSpaceMap,
+    wavelength: Option<f64>,
+) -> napi::Result<ReciprocalSpaceQ> {
+    let wavelength = wavelength
+        .or(map.metadata.wavelength)
+        .ok_or_else(|| napi::Error::from_reason("Wavelength not found, cannot convert to Q"))?;
+    let map = geddes::ReciprocalSpaceMap {
+        scan_axis: map.scan_axis,
+        step_axis: map.step_axis,
+        step_positions: map.step_positions,
+        omega: map.omega,
+        two_theta: map.two_theta,
+        intensities: map.intensities,
+        attenuation: map.attenuation,
+        metadata: geddes::Metadata::default(),
+    };
+    let (qx, qz) = map.to_q_with_wavelength(wavelength);
+    Ok(ReciprocalSpaceQ { qx, qz })
+}
+
+#[napi]
+pub fn diagnose_bruker_raw(path: String) -> napi::Result<BrukerRawDiagnostics> {
+    geddes::diagnose_bruker_raw(path)
+        .map(Into::into)
+        .map_err(to_napi_error)
 }
 
 #[napi]
-pub fn read_bytes(data: Buffer, filename: String) -> napi::Result<Pattern> {
-    geddes::read_bytes(data.as_ref(), &filename)
+pub fn diagnose_bruker_raw_bytes(data: Buffer) -> napi::Result<BrukerRawDiagnostics> {
+    geddes::diagnose_bruker_raw_bytes(data.as_ref())
         .map(Into::into)
         .map_err(to_napi_error)
 }
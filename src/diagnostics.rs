@@ -0,0 +1,62 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How the intensity block of a Bruker RAW file was located.
+///
+/// Structured decodes read every offset from the range header and have a
+/// confidence of 1. When the heuristic fallback is used, `rejected` lists the
+/// competing candidates and their scores so callers can flag ambiguous loads.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BrukerRawDiagnostics {
+    /// Format version from the magic bytes (`1` to `4`), if recognized.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// `true` if the block was found by the heuristic search.
+    pub heuristic: bool,
+    /// The candidate used to build the pattern, if any was usable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected: Option<BrukerCandidate>,
+    /// Candidates that were considered and not used, best first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<BrukerCandidate>,
+    /// Between 0 and 1: `1 - runner-up score / best score` for heuristic
+    /// decodes, 1 for structured ones and 0 if nothing was found.
+    pub confidence: f64,
+}
+
+/// A candidate intensity block and axis location in a Bruker RAW file.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BrukerCandidate {
+    /// Data layout: `structured`, `interleaved` (`f32` value + `u32` status)
+    /// or `plain_f32`.
+    pub layout: String,
+    /// Number of data points.
+    pub count: usize,
+    /// Bytes per data point.
+    pub stride: usize,
+    /// Byte offset of the first data point.
+    pub data_offset: usize,
+    /// Byte offset of the `f32` intensity within a data point.
+    pub value_offset: usize,
+    /// Byte offset of the start angle.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_offset: Option<usize>,
+    /// Byte offset of the step width.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step_offset: Option<usize>,
+    /// Start angle read at `start_offset`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<f64>,
+    /// Step width read at `step_offset`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<f64>,
+    /// Heuristic plausibility score; higher is better.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    /// Why the candidate was not used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
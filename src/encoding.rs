@@ -0,0 +1,123 @@
+use std::fmt;
+
+/// Character encoding of a text file, as guessed by [`detect_encoding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TextEncoding {
+    /// UTF-8 (or ASCII), with or without a byte order mark.
+    Utf8,
+    /// UTF-16, little endian.
+    Utf16Le,
+    /// UTF-16, big endian.
+    Utf16Be,
+    /// ISO 8859-1, used when the bytes are not valid UTF-8.
+    Latin1,
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::Latin1 => "ISO-8859-1",
+        })
+    }
+}
+
+/// Text decoded from raw file bytes.
+#[derive(Debug)]
+pub(crate) struct DecodedText {
+    pub text: String,
+    pub encoding: TextEncoding,
+    /// Length in bytes of the byte order mark, 0 if there was none.
+    pub bom_len: usize,
+}
+
+/// Guesses the encoding of `bytes` and the length of its byte order mark.
+///
+/// Byte order marks decide first. Without one, ASCII text encoded as UTF-16
+/// is recognized by its NUL high bytes, valid UTF-8 is taken as is, and
+/// anything else is treated as Latin-1.
+pub(crate) fn detect_encoding(bytes: &[u8]) -> (TextEncoding, usize) {
+    if bytes.starts_with(b"\xef\xbb\xbf") {
+        (TextEncoding::Utf8, 3)
+    } else if bytes.starts_with(b"\xff\xfe") {
+        (TextEncoding::Utf16Le, 2)
+    } else if bytes.starts_with(b"\xfe\xff") {
+        (TextEncoding::Utf16Be, 2)
+    } else if let Some(encoding) = detect_utf16(bytes) {
+        (encoding, 0)
+    } else if std::str::from_utf8(bytes).is_ok() {
+        (TextEncoding::Utf8, 0)
+    } else {
+        (TextEncoding::Latin1, 0)
+    }
+}
+
+/// Recognizes BOM-less UTF-16 from code units whose high byte is NUL.
+///
+/// Every NUL byte has to sit on the same side of its code unit, and at least
+/// half of the units have to be NUL-padded ASCII. Binary data rarely
+/// satisfies both.
+fn detect_utf16(bytes: &[u8]) -> Option<TextEncoding> {
+    let units = bytes.chunks_exact(2);
+    let total = units.len();
+    if total < 2 {
+        return None;
+    }
+    let (mut low_nul, mut high_nul) = (0usize, 0usize);
+    for unit in units {
+        match (unit[0] == 0, unit[1] == 0) {
+            (true, true) => return None,
+            (true, false) => low_nul += 1,
+            (false, true) => high_nul += 1,
+            (false, false) => {}
+        }
+    }
+    if low_nul == 0 && high_nul * 2 >= total {
+        Some(TextEncoding::Utf16Le)
+    } else if high_nul == 0 && low_nul * 2 >= total {
+        Some(TextEncoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if `bytes` look like text in one of the supported
+/// encodings rather than binary data.
+pub(crate) fn is_text(bytes: &[u8]) -> bool {
+    !bytes.contains(&0)
+        || matches!(
+            detect_encoding(bytes).0,
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be
+        )
+}
+
+/// Decodes `bytes` to a string, dropping the byte order mark.
+///
+/// Invalid UTF-16 sequences become U+FFFD.
+pub(crate) fn decode_text(bytes: Vec<u8>) -> DecodedText {
+    let (encoding, bom_len) = detect_encoding(&bytes);
+    let body = &bytes[bom_len..];
+    let text = match encoding {
+        TextEncoding::Utf8 if bom_len == 0 => {
+            String::from_utf8(bytes).expect("validated as UTF-8 by detect_encoding")
+        }
+        TextEncoding::Utf8 => String::from_utf8_lossy(body).into_owned(),
+        TextEncoding::Utf16Le => decode_utf16(body, u16::from_le_bytes),
+        TextEncoding::Utf16Be => decode_utf16(body, u16::from_be_bytes),
+        TextEncoding::Latin1 => body.iter().map(|&b| char::from(b)).collect(),
+    };
+    DecodedText {
+        text,
+        encoding,
+        bom_len,
+    }
+}
+
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
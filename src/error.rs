@@ -1,3 +1,7 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::io;
 use thiserror::Error as ThisError;
 
@@ -14,8 +18,13 @@ pub enum Error {
     Zip(#[from] zip::result::ZipError),
 
     /// The file content could not be parsed correctly.
-    #[error("Parse error: {0}")]
-    Parse(String),
+    #[error("Parse error: {message}{}", location.as_ref().map(|l| format!(" at {l}")).unwrap_or_default())]
+    Parse {
+        /// What went wrong.
+        message: String,
+        /// Where it went wrong, for line-based text formats.
+        location: Option<Location>,
+    },
 
     /// The file format is not recognized.
     #[error("Unknown format")]
@@ -25,3 +34,45 @@ pub enum Error {
     #[error("File not found in archive: {0}")]
     FileNotFoundInArchive(String),
 }
+
+impl Error {
+    /// Creates a [`Error::Parse`] without a location.
+    pub fn parse(message: impl Into<String>) -> Self {
+        Error::Parse {
+            message: message.into(),
+            location: None,
+        }
+    }
+
+    /// Creates a [`Error::Parse`] pointing at `location`.
+    pub fn parse_at(message: impl Into<String>, location: Location) -> Self {
+        Error::Parse {
+            message: message.into(),
+            location: Some(location),
+        }
+    }
+}
+
+/// Position of the offending line in a text file.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Byte offset of the start of the line. For UTF-16 and Latin-1 files
+    /// it counts bytes of the text decoded to UTF-8.
+    pub offset: u64,
+    /// The offending line, without surrounding whitespace, or the offending
+    /// value for XML formats.
+    pub text: String,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {} (byte {}): {:?}",
+            self.line, self.offset, self.text
+        )
+    }
+}
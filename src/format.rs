@@ -0,0 +1,196 @@
+use crate::encoding::{decode_text, is_text};
+use crate::error::Error;
+use crate::parser::is_rasx_profile;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom};
+use zip::ZipArchive;
+
+/// Number of leading bytes inspected by [`detect_format`].
+const SNIFF_LEN: u64 = 64 * 1024;
+
+/// A diffraction file format supported by the built-in parsers.
+///
+/// More formats may be added, so matches need a wildcard arm.
+#[cfg_attr(feature = "python", pyclass(eq, eq_int, from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Format {
+    /// GSAS standard powder data (`.raw`, `.gsa`, `.gss`).
+    GsasRaw,
+    /// Bruker/Siemens binary RAW, versions 1 to 4 (`.raw`).
+    BrukerRaw,
+    /// Rigaku SmartLab archive (`.rasx`).
+    Rasx,
+    /// PANalytical XRDML (`.xrdml`).
+    Xrdml,
+    /// Whitespace-separated columns (`.xy`, `.xye`).
+    Xy,
+    /// Comma-separated columns (`.csv`).
+    Csv,
+}
+
+/// The result of [`detect_format`]: the most likely format and how sure the
+/// detector is about it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct FormatDetection {
+    /// The detected format.
+    pub format: Format,
+    /// Between 0 and 1. Magic bytes, ZIP entries and XML root elements give
+    /// 1; plain numeric columns give at most 0.8.
+    pub confidence: f64,
+}
+
+/// Detects the format of a file from its content, ignoring its name.
+///
+/// Inspects magic bytes, ZIP entries, XML root elements, GSAS `BANK` headers
+/// and the shape of numeric columns. The reader is rewound to the start
+/// afterwards. Returns `None` if the content matches no known format.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use geddes::{detect_format, Format};
+///
+/// let mut cursor = Cursor::new(b"10.0 100.0\n10.1 105.0\n");
+/// let detection = detect_format(&mut cursor).unwrap().unwrap();
+/// assert_eq!(detection.format, Format::Xy);
+/// ```
+pub fn detect_format<R: Read + Seek>(reader: &mut R) -> Result<Option<FormatDetection>, Error> {
+    reader.seek(SeekFrom::Start(0))?;
+    let mut head = Vec::new();
+    reader.by_ref().take(SNIFF_LEN).read_to_end(&mut head)?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let detection = if head.starts_with(b"PK\x03\x04") {
+        detect_zip(reader)
+    } else {
+        detect_head(&head, head.len() as u64 == SNIFF_LEN)
+    };
+    reader.seek(SeekFrom::Start(0))?;
+    Ok(detection)
+}
+
+fn detection(format: Format, confidence: f64) -> Option<FormatDetection> {
+    Some(FormatDetection { format, confidence })
+}
+
+/// Recognizes RASX archives by their `Profile*.txt` entries.
+fn detect_zip<R: Read + Seek>(reader: &mut R) -> Option<FormatDetection> {
+    let archive = ZipArchive::new(reader).ok()?;
+    let has_profile = archive.file_names().any(is_rasx_profile);
+    if has_profile {
+        detection(Format::Rasx, 1.0)
+    } else {
+        None
+    }
+}
+
+/// Classifies the leading bytes of a non-archive file.
+///
+/// `truncated` tells whether `head` stops before the end of the file, in which
+/// case its last line is ignored.
+fn detect_head(head: &[u8], truncated: bool) -> Option<FormatDetection> {
+    let is_binary = !is_text(head);
+    if head.starts_with(b"RAW") && is_binary {
+        let known = [b"RAW4.00".as_slice(), b"RAW1.01", b"RAW2", b"RAW "]
+            .iter()
+            .any(|magic| head.starts_with(magic));
+        return detection(Format::BrukerRaw, if known { 1.0 } else { 0.8 });
+    }
+    if is_binary {
+        return None;
+    }
+
+    let text = decode_text(head.to_vec()).text;
+    let text = text.trim_start();
+    if text.starts_with('<') {
+        return detect_xml(text);
+    }
+
+    let mut lines: Vec<&str> = text.lines().collect();
+    if truncated {
+        lines.pop();
+    }
+    if lines.iter().any(|line| is_gsas_bank_line(line)) {
+        return detection(Format::GsasRaw, 0.95);
+    }
+    detect_columns(&lines)
+}
+
+/// Recognizes XRDML by its `xrdMeasurements` root element.
+fn detect_xml(text: &str) -> Option<FormatDetection> {
+    let mut rest = text;
+    // Skip the XML declaration, processing instructions and comments.
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("<?") {
+            rest = &after[after.find("?>")? + 2..];
+        } else if let Some(after) = rest.strip_prefix("<!") {
+            rest = &after[after.find('>')? + 1..];
+        } else {
+            break;
+        }
+    }
+    let name = rest
+        .strip_prefix('<')?
+        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .next()?;
+    let local_name = name.rsplit(':').next().unwrap_or(name);
+    if local_name == "xrdMeasurements" {
+        detection(Format::Xrdml, 1.0)
+    } else if text.contains("www.xrdml.com") {
+        detection(Format::Xrdml, 0.6)
+    } else {
+        None
+    }
+}
+
+/// Returns `true` for a GSAS `BANK IBANK NCHAN NREC BINTYP ...` line.
+fn is_gsas_bank_line(line: &str) -> bool {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    parts.len() >= 5
+        && parts[0] == "BANK"
+        && parts[1..4].iter().all(|p| p.parse::<u64>().is_ok())
+        && parts[4]
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Recognizes two- or three-column numeric text as XY or CSV.
+///
+/// The confidence grows with the share of lines that hold at least two
+/// numbers, up to 0.8 when every line does.
+fn detect_columns(lines: &[&str]) -> Option<FormatDetection> {
+    let numeric =
+        |parts: &[&str]| parts.len() >= 2 && parts.iter().all(|p| p.parse::<f64>().is_ok());
+
+    let (mut total, mut xy, mut csv) = (0usize, 0usize, 0usize);
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+        total += 1;
+        if line.contains(',') {
+            let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+            if numeric(&parts) {
+                csv += 1;
+            }
+        } else {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if numeric(&parts) {
+                xy += 1;
+            }
+        }
+    }
+
+    let share = (xy + csv) as f64 / total.max(1) as f64;
+    if xy + csv == 0 || share < 0.5 {
+        return None;
+    }
+    let format = if csv > xy { Format::Csv } else { Format::Xy };
+    detection(format, 0.8 * share)
+}
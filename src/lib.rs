@@ -3,26 +3,49 @@
 //! `geddes` is a library for loading and parsing various diffraction pattern file formats.
 //! It supports common formats like `.raw`, `.rasx`, `.xrdml`, `.xy` / `.xye`, and `.csv`.
 
+mod diagnostics;
+mod encoding;
 mod error;
+mod format;
+mod map;
+mod metadata;
+mod options;
 mod parser;
+mod registry;
+mod report;
 
 #[cfg(feature = "python")]
 mod python;
 
-pub use error::Error;
+pub use diagnostics::{BrukerCandidate, BrukerRawDiagnostics};
+use encoding::is_text;
+pub use error::{Error, Location};
+pub use format::{detect_format, Format, FormatDetection};
+pub use map::{Grid, ReciprocalSpaceMap};
+pub use metadata::{
+    AxisSetting, BankHeader, MeasurementConditions, Metadata, Radiation, Scan, XrayGenerator,
+};
+pub use options::ReadOptions;
 use parser::{
-    parse_bruker_raw, parse_csv, parse_gsas_raw, parse_rasx, parse_xrdml, parse_xy, ParsedPattern,
+    parse_bruker_raw, parse_bruker_raw_all, parse_bruker_raw_diagnostics, parse_csv,
+    parse_gsas_raw, parse_gsas_raw_all, parse_rasx, parse_rasx_all, parse_xrdml, parse_xrdml_all,
+    parse_xrdml_map, parse_xy, ParsedPattern,
 };
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
+pub use registry::{register_reader, registry, FormatRegistry, PatternReader, ReadSeek};
+pub use report::{ReadReport, Warning, WarningKind};
 use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
 
 /// Represents a diffraction pattern with position, intensity, and optional error.
-#[cfg_attr(feature = "python", pyclass(get_all))]
+///
+/// Fields may be added, so build patterns with [`Pattern::new`].
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[non_exhaustive]
 pub struct Pattern {
     /// The x-axis values (e.g., 2-theta or Q).
     pub x: Vec<f64>,
@@ -31,24 +54,60 @@ pub struct Pattern {
     /// The uncertainty/error values, if available.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub e: Option<Vec<f64>>,
+    /// Per-point attenuator/correction factors, if the file records them
+    /// (RASX profile column, XRDML `beamAttenuationFactors`).
+    ///
+    /// While present, `y` holds the raw counts; the corrected intensity is
+    /// `y * attenuation`. See [`Pattern::apply_attenuation`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attenuation: Option<Vec<f64>>,
+    /// Instrument and sample metadata read from the source file.
+    #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+    pub metadata: Metadata,
 }
 
 impl Pattern {
     /// Creates a new diffraction pattern, returning an error if lengths are inconsistent.
     pub fn new(x: Vec<f64>, y: Vec<f64>, e: Option<Vec<f64>>) -> Result<Self, Error> {
         if x.len() != y.len() {
-            return Err(Error::Parse(
-                "x and y must have the same length".into(),
-            ));
+            return Err(Error::parse("x and y must have the same length"));
         }
         if let Some(ref e_vec) = e {
             if e_vec.len() != x.len() {
-                return Err(Error::Parse(
-                    "e must have the same length as x and y".into(),
-                ));
+                return Err(Error::parse("e must have the same length as x and y"));
+            }
+        }
+        Ok(Pattern {
+            x,
+            y,
+            e,
+            attenuation: None,
+            metadata: Metadata::default(),
+        })
+    }
+
+    /// Multiplies the attenuation factors into `y` (and `e`).
+    ///
+    /// After this call `y` holds corrected intensities and `attenuation` is
+    /// `None`. Does nothing if the pattern has no attenuation factors.
+    pub fn apply_attenuation(&mut self) {
+        let Some(factors) = self.attenuation.take() else {
+            return;
+        };
+        for (value, factor) in self.y.iter_mut().zip(&factors) {
+            *value *= factor;
+        }
+        if let Some(e) = self.e.as_mut() {
+            for (value, factor) in e.iter_mut().zip(&factors) {
+                *value *= factor;
             }
         }
-        Ok(Pattern { x, y, e })
+    }
+
+    /// Attaches metadata to the pattern.
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
     }
 }
 
@@ -58,13 +117,17 @@ impl From<ParsedPattern> for Pattern {
             x: data.x,
             y: data.y,
             e: data.e,
+            attenuation: data.attenuation,
+            metadata: data.metadata,
         }
     }
 }
 
 /// Load a pattern from a file path.
 ///
-/// Format is determined automatically by the file extension.
+/// Format is determined automatically by the file extension. When the
+/// extension is unknown, or the file does not parse as the format it names,
+/// the format is detected from the content (see [`detect_format`]).
 ///
 /// # Examples
 ///
@@ -75,10 +138,27 @@ impl From<ParsedPattern> for Pattern {
 /// println!("Loaded {} points", pattern.x.len());
 /// ```
 pub fn read<P: AsRef<Path>>(path: P) -> Result<Pattern, Error> {
+    read_with(path, &ReadOptions::new())
+}
+
+/// Load a pattern from a file path with parser options.
+///
+/// See [`read`] and [`ReadOptions`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use geddes::{read_with, ReadOptions};
+///
+/// let options = ReadOptions::new().xrdml_axis("Omega");
+/// let pattern = read_with("rocking_curve.xrdml", &options).expect("Failed to load file");
+/// println!("Loaded {} points", pattern.x.len());
+/// ```
+pub fn read_with<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result<Pattern, Error> {
     let path = path.as_ref();
     let file = File::open(path)?;
     let filename = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
-    read_reader(file, filename)
+    read_reader_with(file, filename, options)
 }
 
 /// Load a pattern from any reader that implements Read + Seek.
@@ -89,7 +169,8 @@ pub fn read<P: AsRef<Path>>(path: P) -> Result<Pattern, Error> {
 /// # Arguments
 ///
 /// * `reader` - The reader to read from. Must implement `Read` and `Seek`.
-/// * `filename` - The name of the file (used to determine format via extension).
+/// * `filename` - The name of the file (used to determine format via extension;
+///   the content is inspected when the extension is unknown or wrong).
 ///
 /// # Examples
 ///
@@ -106,45 +187,303 @@ pub fn read_reader<R: Read + Seek>(
     reader: R,
     filename: &str,
 ) -> Result<Pattern, Error> {
-    let ext = Path::new(filename)
-        .extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("")
-        .to_lowercase();
-
-    let mut reader = reader;
-    let data = match ext.as_str() {
-        "raw" => {
-            // Check for binary (Bruker) vs Text (GSAS)
-            let mut buffer = [0u8; 1024];
-            let bytes_read = reader.read(&mut buffer)?;
-            reader.seek(SeekFrom::Start(0))?;
-
-            // Simple heuristic: if we find null bytes, assume binary.
-            let chunk = &buffer[..bytes_read];
-            let is_binary = chunk.iter().any(|&b| b == 0);
-
-            // GSAS usually starts with a title line or BANK, and is text.
-            // Bruker binary usually has non-text bytes.
-
-            if is_binary {
-                parse_bruker_raw(reader)?
-            } else {
-                parse_gsas_raw(reader)?
-            }
-        }
-        "rasx" => parse_rasx(reader)?,
-        "xrdml" => parse_xrdml(reader)?,
-        "xy" | "xye" => parse_xy(reader)?,
-        "csv" => parse_csv(reader)?,
-        _ => return Err(Error::UnknownFormat),
-    };
+    read_reader_with(reader, filename, &ReadOptions::new())
+}
 
-    Ok(data.into())
+/// Load a pattern from a reader with parser options.
+///
+/// See [`read_reader`] and [`ReadOptions`].
+pub fn read_reader_with<R: Read + Seek>(
+    reader: R,
+    filename: &str,
+    options: &ReadOptions,
+) -> Result<Pattern, Error> {
+    registry().read_reader_with(reader, filename, options)
 }
 
 /// Load a pattern from in-memory bytes with a filename hint.
 pub fn read_bytes<B: AsRef<[u8]>>(bytes: B, filename: &str) -> Result<Pattern, Error> {
+    read_bytes_with(bytes, filename, &ReadOptions::new())
+}
+
+/// Load a pattern from in-memory bytes with a filename hint and parser options.
+pub fn read_bytes_with<B: AsRef<[u8]>>(
+    bytes: B,
+    filename: &str,
+    options: &ReadOptions,
+) -> Result<Pattern, Error> {
+    let cursor = Cursor::new(bytes.as_ref());
+    read_reader_with(cursor, filename, options)
+}
+
+/// Load a pattern together with the warnings collected while parsing.
+///
+/// Lenient parsing skips malformed lines and values; the report lists what
+/// was skipped, how often and where. See [`ReadReport`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use geddes::{read_report, ReadOptions};
+///
+/// let report = read_report("scan.xy", &ReadOptions::new()).expect("Failed to load file");
+/// for warning in &report.warnings {
+///     println!("{:?} x{}: {}", warning.kind, warning.count, warning.message);
+/// }
+/// ```
+pub fn read_report<P: AsRef<Path>>(path: P, options: &ReadOptions) -> Result<ReadReport, Error> {
+    let path = path.as_ref();
+    let file = File::open(path)?;
+    let filename = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+    read_report_reader(file, filename, options)
+}
+
+/// Load a pattern and its parse warnings from a reader with a filename hint.
+///
+/// See [`read_report`].
+pub fn read_report_reader<R: Read + Seek>(
+    reader: R,
+    filename: &str,
+    options: &ReadOptions,
+) -> Result<ReadReport, Error> {
+    registry().read_report_reader(reader, filename, options)
+}
+
+/// Load a pattern and its parse warnings from in-memory bytes with a filename
+/// hint.
+///
+/// See [`read_report`].
+pub fn read_report_bytes<B: AsRef<[u8]>>(
+    bytes: B,
+    filename: &str,
+    options: &ReadOptions,
+) -> Result<ReadReport, Error> {
+    let cursor = Cursor::new(bytes.as_ref());
+    read_report_reader(cursor, filename, options)
+}
+
+/// Load a pattern from a reader with an explicitly chosen format.
+///
+/// Neither the file name nor the content is used to pick the parser, which
+/// removes any ambiguity (e.g. a GSAS `.raw` text file containing a NUL byte).
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use geddes::{read_with_format, Format, ReadOptions};
+///
+/// let data = b"10.0 100.0\n10.1 105.0";
+/// let pattern = read_with_format(Cursor::new(data), Format::Xy, &ReadOptions::new()).unwrap();
+/// assert_eq!(pattern.x.len(), 2);
+/// ```
+pub fn read_with_format<R: Read + Seek>(
+    reader: R,
+    format: Format,
+    options: &ReadOptions,
+) -> Result<Pattern, Error> {
+    Ok(finish_report(parse_format(reader, format, options)?, options).pattern)
+}
+
+/// Load every pattern stored in a file.
+///
+/// Container formats can hold several scans; this returns all of them in file
+/// order. Single-pattern formats return a one-element vector.
+///
+/// # Examples
+///
+/// ```no_run
+/// use geddes::read_all;
+///
+/// let patterns = read_all("tests/data/rasx/sample.rasx").expect("Failed to load file");
+/// println!("Loaded {} scans", patterns.len());
+/// ```
+pub fn read_all<P: AsRef<Path>>(path: P) -> Result<Vec<Pattern>, Error> {
+    read_all_with(path, &ReadOptions::new())
+}
+
+/// Load every pattern stored in a file with parser options.
+///
+/// See [`read_all`] and [`ReadOptions`].
+pub fn read_all_with<P: AsRef<Path>>(
+    path: P,
+    options: &ReadOptions,
+) -> Result<Vec<Pattern>, Error> {
+    let path = path.as_ref();
+    let file = File::open(path)?;
+    let filename = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+    read_all_reader_with(file, filename, options)
+}
+
+/// Load every pattern from any reader that implements Read + Seek.
+///
+/// See [`read_all`] and [`read_reader`].
+pub fn read_all_reader<R: Read + Seek>(reader: R, filename: &str) -> Result<Vec<Pattern>, Error> {
+    read_all_reader_with(reader, filename, &ReadOptions::new())
+}
+
+/// Load every pattern from a reader with parser options.
+///
+/// See [`read_all_reader`] and [`ReadOptions`].
+pub fn read_all_reader_with<R: Read + Seek>(
+    reader: R,
+    filename: &str,
+    options: &ReadOptions,
+) -> Result<Vec<Pattern>, Error> {
+    registry().read_all_reader_with(reader, filename, options)
+}
+
+/// Load every pattern from in-memory bytes with a filename hint.
+pub fn read_all_bytes<B: AsRef<[u8]>>(bytes: B, filename: &str) -> Result<Vec<Pattern>, Error> {
+    read_all_bytes_with(bytes, filename, &ReadOptions::new())
+}
+
+/// Load every pattern from in-memory bytes with a filename hint and parser
+/// options.
+pub fn read_all_bytes_with<B: AsRef<[u8]>>(
+    bytes: B,
+    filename: &str,
+    options: &ReadOptions,
+) -> Result<Vec<Pattern>, Error> {
     let cursor = Cursor::new(bytes.as_ref());
-    read_reader(cursor, filename)
+    read_all_reader_with(cursor, filename, options)
+}
+
+/// Load every scan of an XRDML file as a reciprocal space map.
+///
+/// An XRDML file with several Omega-2Theta (or 2Theta) scans stepped in
+/// Omega, or rocking curves stepped in 2Theta, forms a 2D map. Each scan
+/// becomes a row holding the Omega, 2Theta and intensity of its points;
+/// [`ReciprocalSpaceMap::to_q`] converts them to Qx/Qz.
+///
+/// # Examples
+///
+/// ```no_run
+/// use geddes::{read_xrdml_map, ReadOptions};
+///
+/// let map = read_xrdml_map("rsm.xrdml", &ReadOptions::new()).expect("Failed to load file");
+/// let (qx, qz) = map.to_q().expect("No wavelength");
+/// println!("{} scans stepped in {}", qx.len(), map.step_axis);
+/// ```
+pub fn read_xrdml_map<P: AsRef<Path>>(
+    path: P,
+    options: &ReadOptions,
+) -> Result<ReciprocalSpaceMap, Error> {
+    read_xrdml_map_reader(File::open(path)?, options)
+}
+
+/// Load an XRDML reciprocal space map from any reader.
+///
+/// See [`read_xrdml_map`].
+pub fn read_xrdml_map_reader<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<ReciprocalSpaceMap, Error> {
+    let scans = parse_xrdml_map(reader, options)?
+        .into_iter()
+        .map(|scan| {
+            let pattern = finish_report(scan.data, options).pattern;
+            (scan.omega, scan.two_theta, pattern)
+        })
+        .collect();
+    Ok(ReciprocalSpaceMap::from_scans(scans))
+}
+
+/// Load an XRDML reciprocal space map from in-memory bytes.
+///
+/// See [`read_xrdml_map`].
+pub fn read_xrdml_map_bytes<B: AsRef<[u8]>>(
+    bytes: B,
+    options: &ReadOptions,
+) -> Result<ReciprocalSpaceMap, Error> {
+    read_xrdml_map_reader(bytes.as_ref(), options)
+}
+
+/// Report how the data block of a Bruker `.raw` file was located.
+///
+/// Use this to flag low-confidence loads: files decoded from their range
+/// headers report a confidence of 1, while files that need the heuristic
+/// search list the chosen layout, offsets and competing candidates.
+///
+/// # Examples
+///
+/// ```no_run
+/// use geddes::diagnose_bruker_raw;
+///
+/// let report = diagnose_bruker_raw("scan.raw").expect("Failed to read file");
+/// if report.confidence < 0.5 {
+///     println!("Ambiguous axis: {:?}", report.selected);
+/// }
+/// ```
+pub fn diagnose_bruker_raw<P: AsRef<Path>>(path: P) -> Result<BrukerRawDiagnostics, Error> {
+    parse_bruker_raw_diagnostics(File::open(path)?)
+}
+
+/// Report how the data block of in-memory Bruker `.raw` bytes was located.
+///
+/// See [`diagnose_bruker_raw`].
+pub fn diagnose_bruker_raw_bytes<B: AsRef<[u8]>>(bytes: B) -> Result<BrukerRawDiagnostics, Error> {
+    parse_bruker_raw_diagnostics(bytes.as_ref())
+}
+
+/// Converts parsed data into a pattern and its warnings, applying `options`.
+fn finish_report(mut data: ParsedPattern, options: &ReadOptions) -> ReadReport {
+    let warnings = std::mem::take(&mut data.warnings);
+    let mut pattern = Pattern::from(data);
+    if options.apply_attenuation {
+        pattern.apply_attenuation();
+    }
+    ReadReport { pattern, warnings }
+}
+
+/// Parses the first pattern of `reader` as `format`.
+fn parse_format<R: Read + Seek>(
+    reader: R,
+    format: Format,
+    options: &ReadOptions,
+) -> Result<ParsedPattern, Error> {
+    match format {
+        Format::GsasRaw => parse_gsas_raw(reader, options),
+        Format::BrukerRaw => parse_bruker_raw(reader),
+        Format::Rasx => parse_rasx(reader, options),
+        Format::Xrdml => parse_xrdml(reader, options),
+        Format::Xy => parse_xy(reader, options),
+        Format::Csv => parse_csv(reader, options),
+    }
+}
+
+/// Parses every pattern of `reader` as `format`.
+fn parse_format_all<R: Read + Seek>(
+    reader: R,
+    format: Format,
+    options: &ReadOptions,
+) -> Result<Vec<ParsedPattern>, Error> {
+    match format {
+        Format::GsasRaw => parse_gsas_raw_all(reader, options),
+        Format::BrukerRaw => parse_bruker_raw_all(reader),
+        Format::Rasx => parse_rasx_all(reader, options),
+        Format::Xrdml => parse_xrdml_all(reader, options),
+        _ => parse_format(reader, format, options).map(|data| vec![data]),
+    }
+}
+
+/// Returns `true` if the first KiB of `reader` contains a NUL byte and is not
+/// UTF-16 text.
+///
+/// The reader is rewound to the start afterwards.
+fn is_binary<R: Read + Seek + ?Sized>(reader: &mut R) -> Result<bool, Error> {
+    let mut buffer = [0u8; 1024];
+    let bytes_read = reader.read(&mut buffer)?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    Ok(!is_text(&buffer[..bytes_read]))
+}
+
+/// Returns the lowercase extension of `filename`, or an empty string.
+fn file_extension(filename: &str) -> String {
+    Path::new(filename)
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_lowercase()
 }
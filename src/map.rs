@@ -0,0 +1,155 @@
+use crate::error::Error;
+use crate::metadata::Metadata;
+use crate::Pattern;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// Values of every point of a map, one row per scan.
+pub type Grid = Vec<Vec<f64>>;
+
+/// A reciprocal space map: scans along one axis, stepped along another.
+///
+/// Row `i` holds scan `i` and column `j` its point `j`, so `omega[i][j]`,
+/// `two_theta[i][j]` and `intensities[i][j]` describe one point. Rows can
+/// differ in length, e.g. when the last scan was aborted.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReciprocalSpaceMap {
+    /// Axis scanned within each scan, as recorded by the instrument (e.g.
+    /// `Omega-2Theta`).
+    pub scan_axis: String,
+    /// Axis stepped from one scan to the next (`Omega` or `2Theta`).
+    pub step_axis: String,
+    /// Position of the step axis at the first point of each scan.
+    pub step_positions: Vec<f64>,
+    /// Omega of every point, in degrees.
+    pub omega: Vec<Vec<f64>>,
+    /// 2Theta of every point, in degrees.
+    pub two_theta: Vec<Vec<f64>>,
+    /// Intensity of every point, in the unit of the scans.
+    pub intensities: Vec<Vec<f64>>,
+    /// Per-point attenuation factors, if the scans record them.
+    ///
+    /// While present, `intensities` hold the raw counts; see
+    /// [`Pattern::attenuation`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attenuation: Option<Vec<Vec<f64>>>,
+    /// Instrument and sample metadata of the first scan.
+    #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+    pub metadata: Metadata,
+}
+
+impl ReciprocalSpaceMap {
+    /// Builds a map from `(omega, two_theta, pattern)` for every scan.
+    pub(crate) fn from_scans(scans: Vec<(Vec<f64>, Vec<f64>, Pattern)>) -> Self {
+        let metadata = scans
+            .first()
+            .map(|(_, _, pattern)| pattern.metadata.clone())
+            .unwrap_or_default();
+        let scan = metadata.scan.as_ref();
+        let scan_axis = scan.and_then(|s| s.axis.clone()).unwrap_or_default();
+        let x_axis = scan.and_then(|s| s.x_axis.clone());
+        let step_axis = step_axis(&scans, x_axis.as_deref() == Some("Omega"));
+        let has_attenuation = scans.iter().any(|(_, _, p)| p.attenuation.is_some());
+
+        let mut map = ReciprocalSpaceMap {
+            scan_axis,
+            step_axis: step_axis.to_string(),
+            step_positions: Vec::with_capacity(scans.len()),
+            omega: Vec::with_capacity(scans.len()),
+            two_theta: Vec::with_capacity(scans.len()),
+            intensities: Vec::with_capacity(scans.len()),
+            attenuation: has_attenuation.then(Vec::new),
+            metadata,
+        };
+        for (omega, two_theta, pattern) in scans {
+            let first = if step_axis == "Omega" {
+                omega.first()
+            } else {
+                two_theta.first()
+            };
+            map.step_positions.push(first.copied().unwrap_or(f64::NAN));
+            if let Some(rows) = map.attenuation.as_mut() {
+                let count = pattern.y.len();
+                rows.push(pattern.attenuation.unwrap_or_else(|| vec![1.0; count]));
+            }
+            map.omega.push(omega);
+            map.two_theta.push(two_theta);
+            map.intensities.push(pattern.y);
+        }
+        map
+    }
+
+    /// Converts every point to reciprocal space coordinates `(qx, qz)` in
+    /// inverse angstrom, using the wavelength in `metadata`.
+    ///
+    /// See [`to_q_with_wavelength`](Self::to_q_with_wavelength).
+    pub fn to_q(&self) -> Result<(Grid, Grid), Error> {
+        let wavelength = self
+            .metadata
+            .wavelength
+            .ok_or_else(|| Error::parse("Wavelength not found, cannot convert to Q"))?;
+        Ok(self.to_q_with_wavelength(wavelength))
+    }
+
+    /// Converts every point to `(qx, qz)` in inverse angstrom for a
+    /// wavelength in angstrom.
+    ///
+    /// With `k = 2π / λ`, `qx = k (cos ω − cos(2θ − ω))` and
+    /// `qz = k (sin ω + sin(2θ − ω))`, so a symmetric point (`ω = θ`) has
+    /// `qx = 0` and `qz = 4π sin θ / λ`.
+    pub fn to_q_with_wavelength(&self, wavelength: f64) -> (Grid, Grid) {
+        let k = 2.0 * PI / wavelength;
+        self.omega
+            .iter()
+            .zip(&self.two_theta)
+            .map(|(omega, two_theta)| {
+                omega
+                    .iter()
+                    .zip(two_theta)
+                    .map(|(omega, two_theta)| {
+                        let omega = omega.to_radians();
+                        let exit = two_theta.to_radians() - omega;
+                        (
+                            k * (omega.cos() - exit.cos()),
+                            k * (omega.sin() + exit.sin()),
+                        )
+                    })
+                    .unzip()
+            })
+            .unzip()
+    }
+}
+
+/// Returns the axis that changes from one scan to the next.
+///
+/// That is the one of Omega and 2Theta whose first position varies between
+/// scans. If both vary, it is the one that x is not taken from
+/// (`omega_scans` tells whether x is Omega); a single scan is taken as
+/// stepped in Omega.
+fn step_axis(scans: &[(Vec<f64>, Vec<f64>, Pattern)], omega_scans: bool) -> &'static str {
+    let varies = |values: Vec<f64>| {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        max - min > 1e-9
+    };
+    let omega = varies(
+        scans
+            .iter()
+            .filter_map(|(o, _, _)| o.first().copied())
+            .collect(),
+    );
+    let two_theta = varies(
+        scans
+            .iter()
+            .filter_map(|(_, t, _)| t.first().copied())
+            .collect(),
+    );
+    match (omega, two_theta) {
+        (false, true) => "2Theta",
+        (true, true) if omega_scans => "2Theta",
+        _ => "Omega",
+    }
+}
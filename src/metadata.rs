@@ -0,0 +1,273 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Instrument and sample metadata recovered from the source file.
+///
+/// Well-known fields are typed; anything else the parser finds is kept in
+/// `extra` as plain key/value strings. Fields that a format does not record
+/// are left as `None`.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    /// Free-text title of the measurement.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Sample name or identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_name: Option<String>,
+    /// Operator or user who recorded the measurement.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+    /// Diffractometer or system name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instrument: Option<String>,
+    /// X-ray tube anode material (e.g. `Cu`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anode: Option<String>,
+    /// Primary (K-alpha1) wavelength in angstrom.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wavelength: Option<f64>,
+    /// Full set of characteristic wavelengths, when the file lists them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radiation: Option<Radiation>,
+    /// Counting time per step in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counting_time: Option<f64>,
+    /// Goniometer radius in millimetres.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goniometer_radius: Option<f64>,
+    /// Scan axis and range settings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan: Option<Scan>,
+    /// Hardware configuration (generator, detector, optics, axes).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conditions: Option<MeasurementConditions>,
+    /// GSAS `BANK` header the pattern was read from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bank: Option<BankHeader>,
+    /// Measurement start timestamp.
+    ///
+    /// Bruker RAW `MM/DD/YYYY` dates are normalized to ISO 8601
+    /// (`YYYY-MM-DDThh:mm:ss`); other formats keep the text as written in the
+    /// file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<String>,
+    /// Measurement end timestamp, in the same form as `start_time`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<String>,
+    /// Additional format-specific key/value pairs.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+
+impl Metadata {
+    /// Returns `true` if no metadata was recorded.
+    pub fn is_empty(&self) -> bool {
+        *self == Metadata::default()
+    }
+}
+
+/// Characteristic wavelengths of the X-ray source.
+///
+/// Values are kept in the unit written in the file (see `unit`); use
+/// [`Radiation::to_angstrom`] to normalize them.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Radiation {
+    /// The line the instrument was set up for (e.g. `K-Alpha 1`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intended: Option<String>,
+    /// K-alpha1 wavelength.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub k_alpha1: Option<f64>,
+    /// K-alpha2 wavelength.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub k_alpha2: Option<f64>,
+    /// K-beta wavelength.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub k_beta: Option<f64>,
+    /// Intensity ratio of K-alpha2 to K-alpha1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ratio_k_alpha2_k_alpha1: Option<f64>,
+    /// Wavelength unit as written in the file (e.g. `Angstrom`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+impl Radiation {
+    /// Intensity-weighted mean of K-alpha1 and K-alpha2.
+    ///
+    /// Falls back to K-alpha1 when K-alpha2 or the ratio is missing.
+    pub fn k_alpha_average(&self) -> Option<f64> {
+        let k_alpha1 = self.k_alpha1?;
+        match (self.k_alpha2, self.ratio_k_alpha2_k_alpha1) {
+            (Some(k_alpha2), Some(ratio)) if ratio >= 0.0 => {
+                Some((k_alpha1 + ratio * k_alpha2) / (1.0 + ratio))
+            }
+            _ => Some(k_alpha1),
+        }
+    }
+
+    /// Returns a copy with all wavelengths converted to angstrom.
+    ///
+    /// Returns `None` if the unit is not recognized. A missing unit is taken
+    /// to mean angstrom.
+    pub fn to_angstrom(&self) -> Option<Radiation> {
+        let factor = match self.unit.as_deref() {
+            None => 1.0,
+            Some(unit) => angstrom_per_unit(unit)?,
+        };
+        Some(Radiation {
+            intended: self.intended.clone(),
+            k_alpha1: self.k_alpha1.map(|v| v * factor),
+            k_alpha2: self.k_alpha2.map(|v| v * factor),
+            k_beta: self.k_beta.map(|v| v * factor),
+            ratio_k_alpha2_k_alpha1: self.ratio_k_alpha2_k_alpha1,
+            unit: Some("Angstrom".to_string()),
+        })
+    }
+}
+
+/// Conversion factor from a length unit name to angstrom.
+fn angstrom_per_unit(unit: &str) -> Option<f64> {
+    match unit.trim() {
+        "Angstrom" | "angstrom" | "A" | "\u{c5}" | "\u{212b}" => Some(1.0),
+        "nm" => Some(10.0),
+        "pm" => Some(0.01),
+        _ => None,
+    }
+}
+
+/// Scan axis and range settings as recorded by the instrument.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Scan {
+    /// Position of the scan within the file (e.g. XRDML `appendNumber`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
+    /// Name of the scanned axis (e.g. `TwoTheta`, `2Theta`, `Omega`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub axis: Option<String>,
+    /// Axis whose positions make up x, when the format distinguishes it from
+    /// `axis` (e.g. `Omega` for an XRDML `Omega-2Theta` scan).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x_axis: Option<String>,
+    /// Scan mode (e.g. `CONTINUOUS`, `STEP`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// Completion status reported by the instrument (e.g. `Completed`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Programmed start position.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<f64>,
+    /// Programmed end position.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<f64>,
+    /// Programmed step width.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<f64>,
+    /// Scan speed, in `speed_unit`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f64>,
+    /// Unit of `speed` (e.g. `deg/min`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed_unit: Option<String>,
+    /// Unit of the axis positions (e.g. `deg`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_unit: Option<String>,
+    /// Unit of the intensities (e.g. `counts`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intensity_unit: Option<String>,
+}
+
+/// Hardware configuration recorded with a measurement.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MeasurementConditions {
+    /// X-ray generator settings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generator: Option<XrayGenerator>,
+    /// Detector name (e.g. `HyPix3000(H)`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detector: Option<String>,
+    /// Name of the optics configuration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub optics: Option<String>,
+    /// Beam geometry of the optics (e.g. `BB` for Bragg-Brentano).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geometry: Option<String>,
+    /// Installed hardware unit per component (e.g. `IncidentCBO` -> `CBO-E`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hardware: BTreeMap<String, String>,
+    /// Goniometer axes, slits and other positioned components.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub axes: Vec<AxisSetting>,
+}
+
+impl MeasurementConditions {
+    /// Returns `true` if no condition was recorded.
+    pub fn is_empty(&self) -> bool {
+        *self == MeasurementConditions::default()
+    }
+}
+
+/// X-ray generator (tube) settings.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct XrayGenerator {
+    /// Anode target material (e.g. `Cu`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Tube voltage in kV.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voltage: Option<f64>,
+    /// Tube current in mA.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<f64>,
+    /// Focus size description (e.g. `0.4mm x 12mm`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focus: Option<String>,
+}
+
+/// Position and state of a single instrument axis or slit.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AxisSetting {
+    /// Axis name (e.g. `TwoTheta`, `IS`).
+    pub name: String,
+    /// Position unit; empty for named positions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// Position as written in the file; may be numeric or a named setting.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+    /// Calibration offset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<f64>,
+    /// Axis state during the measurement (e.g. `Scan`, `Fixed`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
+/// Parameters of a GSAS RAW `BANK` header line.
+///
+/// `BANK IBANK NCHAN NREC BINTYP BCOEF(1) BCOEF(2) ... TYPE`
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BankHeader {
+    /// Bank number (`IBANK`).
+    pub number: u32,
+    /// Number of data points (`NCHAN`).
+    pub channels: usize,
+    /// Number of data records (`NREC`).
+    pub records: usize,
+    /// Binning type (`BINTYP`, e.g. `CONST`, `RALF`, `SLOG`).
+    pub binning: String,
+    /// Binning coefficients (`BCOEF`), as written in the file.
+    pub coefficients: Vec<f64>,
+    /// Data layout (`STD`, `ESD`, `ALT` or `FXYE`).
+    pub data_type: String,
+}
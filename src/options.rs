@@ -0,0 +1,119 @@
+/// Options that control how a file is parsed.
+///
+/// The defaults reproduce the behavior of [`read`](crate::read). Settings are
+/// chained from [`ReadOptions::new`]:
+///
+/// ```
+/// use geddes::ReadOptions;
+///
+/// let options = ReadOptions::new()
+///     .apply_attenuation(true)
+///     .comment_prefixes(["#", "%"]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadOptions {
+    pub(crate) apply_attenuation: bool,
+    pub(crate) gsas_centidegrees: bool,
+    pub(crate) rasx_data_set: Option<usize>,
+    pub(crate) xrdml_axis: Option<String>,
+    pub(crate) xrdml_counts_per_second: bool,
+    pub(crate) comment_prefixes: Vec<String>,
+    pub(crate) strict: bool,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        ReadOptions {
+            apply_attenuation: false,
+            gsas_centidegrees: true,
+            rasx_data_set: None,
+            xrdml_axis: None,
+            xrdml_counts_per_second: false,
+            comment_prefixes: vec!["#".to_string(), "!".to_string()],
+            strict: false,
+        }
+    }
+}
+
+impl ReadOptions {
+    /// Creates options with every setting at its default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Multiply attenuation factors into the intensities (default `false`).
+    ///
+    /// When off, patterns keep raw counts in `y` and the factors in
+    /// `attenuation`; see [`Pattern::apply_attenuation`](crate::Pattern::apply_attenuation).
+    pub fn apply_attenuation(mut self, apply: bool) -> Self {
+        self.apply_attenuation = apply;
+        self
+    }
+
+    /// Read GSAS `CONST` positions as centidegrees and divide them by 100
+    /// (default `true`).
+    ///
+    /// Turn this off for files that write `BCOEF` and `ALT`/`FXYE` positions
+    /// in degrees.
+    pub fn gsas_centidegrees(mut self, centidegrees: bool) -> Self {
+        self.gsas_centidegrees = centidegrees;
+        self
+    }
+
+    /// Index of the RASX data set (`Profile*.txt`, in archive order) that
+    /// single-pattern reads load.
+    ///
+    /// By default `Data0/Profile0.txt` is loaded, or the first profile if it
+    /// is missing. [`read_all`](crate::read_all) always returns every data set.
+    pub fn rasx_data_set(mut self, index: usize) -> Self {
+        self.rasx_data_set = Some(index);
+        self
+    }
+
+    /// XRDML `positions` axis used for x, e.g. `Omega` or `2Theta`.
+    ///
+    /// By default each scan uses the axis named by its `scanAxis`: the first
+    /// axis of coupled scans such as `Omega-2Theta`, and `2Theta` for `Gonio`
+    /// scans. The chosen axis is recorded in [`Scan::x_axis`](crate::Scan::x_axis).
+    pub fn xrdml_axis(mut self, axis: impl Into<String>) -> Self {
+        self.xrdml_axis = Some(axis.into());
+        self
+    }
+
+    /// Divide XRDML intensities and errors by the `commonCountingTime` to get
+    /// counts per second (default `false`).
+    ///
+    /// Reading fails if a scan records no counting time. Intensities already
+    /// stored as `cps` are left as they are.
+    pub fn xrdml_counts_per_second(mut self, cps: bool) -> Self {
+        self.xrdml_counts_per_second = cps;
+        self
+    }
+
+    /// Line prefixes that mark comments in XY and CSV files (default `#`
+    /// and `!`).
+    pub fn comment_prefixes<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.comment_prefixes = prefixes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Fail on malformed data lines instead of skipping them (default
+    /// `false`).
+    ///
+    /// In strict mode XY and CSV files fail with an [`Error::Parse`](crate::Error::Parse)
+    /// carrying the line number, byte offset and text of the first line that
+    /// has no numeric (x, y) pair, an invalid error column, or a different
+    /// number of columns than the first data line. RASX profiles fail on lines
+    /// without a numeric (x, y) pair or with an invalid attenuation factor,
+    /// XRDML files on the first intensity that is not a number, located by
+    /// its line, and GSAS files on the first record with a value that is not
+    /// a number or an `FXYE` line without numeric x, y and e.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+}
@@ -1,7 +1,15 @@
-use crate::error::Error;
-use quick_xml::events::Event;
+use crate::diagnostics::{BrukerCandidate, BrukerRawDiagnostics};
+use crate::encoding::{decode_text, DecodedText, TextEncoding};
+use crate::error::{Error, Location};
+use crate::metadata::{
+    AxisSetting, BankHeader, MeasurementConditions, Metadata, Radiation, Scan, XrayGenerator,
+};
+use crate::options::ReadOptions;
+use crate::report::{Warning, WarningKind, WarningLog};
+use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
-use std::io::{BufRead, BufReader, Read, Seek};
+use std::collections::HashMap;
+use std::io::{Read, Seek};
 use zip::ZipArchive;
 
 /// Intermediate structure to hold parsed data before converting to the public Pattern struct.
@@ -10,386 +18,2462 @@ pub struct ParsedPattern {
     pub x: Vec<f64>,
     pub y: Vec<f64>,
     pub e: Option<Vec<f64>>,
+    pub attenuation: Option<Vec<f64>>,
+    pub metadata: Metadata,
+    pub warnings: Vec<Warning>,
 }
 
 /// Helper to parse x, y, and optional e from string parts.
-fn parse_columns(parts: &[&str], x: &mut Vec<f64>, y: &mut Vec<f64>, e: &mut Vec<f64>) {
-    if parts.len() >= 2 {
-        if let (Ok(val_x), Ok(val_y)) = (parts[0].parse::<f64>(), parts[1].parse::<f64>()) {
-            x.push(val_x);
-            y.push(val_y);
-            if parts.len() >= 3 {
-                if let Ok(val_e) = parts[2].parse::<f64>() {
-                    e.push(val_e);
+///
+/// Returns the number of values read (2 or 3), or `None` if the line holds no
+/// numeric (x, y) pair.
+fn parse_columns(
+    parts: &[&str],
+    x: &mut Vec<f64>,
+    y: &mut Vec<f64>,
+    e: &mut Vec<f64>,
+) -> Option<usize> {
+    if parts.len() < 2 {
+        return None;
+    }
+    let (Ok(val_x), Ok(val_y)) = (parts[0].parse::<f64>(), parts[1].parse::<f64>()) else {
+        return None;
+    };
+    x.push(val_x);
+    y.push(val_y);
+    if let Some(Ok(val_e)) = parts.get(2).map(|p| p.parse::<f64>()) {
+        e.push(val_e);
+        return Some(3);
+    }
+    Some(2)
+}
+
+/// Returns `true` if `line` starts with one of the comment prefixes.
+fn is_comment(line: &str, options: &ReadOptions) -> bool {
+    options
+        .comment_prefixes
+        .iter()
+        .any(|prefix| !prefix.is_empty() && line.starts_with(prefix.as_str()))
+}
+
+/// Reads `reader` to the end and decodes it as text.
+///
+/// A byte order mark is dropped and recorded in `warnings`, naming `source`.
+fn read_text<R: Read>(
+    mut reader: R,
+    source: &str,
+    warnings: &mut WarningLog,
+) -> Result<DecodedText, Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let decoded = decode_text(bytes);
+    if decoded.bom_len > 0 {
+        warnings.add(
+            WarningKind::ByteOrderMark,
+            format!(
+                "{source} starts with a {} byte order mark",
+                decoded.encoding
+            ),
+            None,
+        );
+    }
+    Ok(decoded)
+}
+
+/// A line of decoded text, without its terminator.
+#[derive(Debug, Clone, Copy)]
+struct TextLine<'a> {
+    /// Line number, starting at 1.
+    number: usize,
+    /// Byte offset of the line start.
+    offset: u64,
+    text: &'a str,
+}
+
+impl TextLine<'_> {
+    fn location(&self) -> Location {
+        Location {
+            line: self.number,
+            offset: self.offset,
+            text: self.text.trim().to_string(),
+        }
+    }
+}
+
+/// Iterates over the lines of `decoded`.
+///
+/// Offsets match the file for UTF-8 input, byte order mark included; for
+/// other encodings they count bytes of the decoded text.
+fn text_lines(decoded: &DecodedText) -> impl Iterator<Item = TextLine<'_>> {
+    let mut offset = match decoded.encoding {
+        TextEncoding::Utf8 => decoded.bom_len as u64,
+        _ => 0,
+    };
+    decoded
+        .text
+        .split_inclusive('\n')
+        .enumerate()
+        .map(move |(index, line)| {
+            let start = offset;
+            offset += line.len() as u64;
+            TextLine {
+                number: index + 1,
+                offset: start,
+                text: line.trim_end_matches(['\n', '\r']),
+            }
+        })
+}
+
+/// Calls `f` for each line of `decoded` with the 1-based line number, the
+/// byte offset of the line start and the line without its terminator.
+///
+/// See [`text_lines`].
+fn for_each_line(
+    decoded: &DecodedText,
+    mut f: impl FnMut(usize, u64, &str) -> Result<(), Error>,
+) -> Result<(), Error> {
+    for line in text_lines(decoded) {
+        f(line.number, line.offset, line.text)?;
+    }
+    Ok(())
+}
+
+/// Records `message` about `line` in `warnings`, or fails with it in strict
+/// mode.
+fn line_issue(
+    options: &ReadOptions,
+    warnings: &mut WarningLog,
+    kind: WarningKind,
+    message: impl Into<String>,
+    line: &TextLine,
+) -> Result<(), Error> {
+    if options.strict {
+        return Err(Error::parse_at(message, line.location()));
+    }
+    warnings.add(kind, message, Some(line.location()));
+    Ok(())
+}
+
+/// Parses x, y and optional e columns from text, one point per line.
+///
+/// The text may be UTF-8, UTF-16 or Latin-1. `split` breaks a line into
+/// fields. Empty and comment lines are skipped.
+/// Lines without a numeric (x, y) pair are skipped too, and e is kept only if
+/// every point has one; both are recorded as warnings. With
+/// [`ReadOptions::strict`] they fail with the location of the offending line
+/// instead.
+fn parse_text_columns<R: Read>(
+    reader: R,
+    source: &str,
+    options: &ReadOptions,
+    split: fn(&str) -> Vec<&str>,
+) -> Result<ParsedPattern, Error> {
+    let mut x = Vec::new();
+    let mut y = Vec::new();
+    let mut e = Vec::new();
+    let mut columns = None;
+    let mut warnings = WarningLog::default();
+    let text = read_text(reader, source, &mut warnings)?;
+
+    for_each_line(&text, |number, offset, line| {
+        let line = line.trim();
+        if line.is_empty() || is_comment(line, options) {
+            return Ok(());
+        }
+        let parts = split(line);
+        let issue = match parse_columns(&parts, &mut x, &mut y, &mut e) {
+            None => Some((
+                WarningKind::SkippedLine,
+                "expected numeric x and y columns".to_string(),
+            )),
+            Some(2) if parts.len() > 2 => Some((
+                WarningKind::InvalidValue,
+                "invalid error column".to_string(),
+            )),
+            Some(read) => match *columns.get_or_insert(read) {
+                expected if expected != read => Some((
+                    WarningKind::InconsistentColumns,
+                    format!("expected {expected} columns, found {read}"),
+                )),
+                _ => None,
+            },
+        };
+        let Some((kind, message)) = issue else {
+            return Ok(());
+        };
+        let location = Location {
+            line: number,
+            offset,
+            text: line.to_string(),
+        };
+        if options.strict {
+            return Err(Error::parse_at(message, location));
+        }
+        warnings.add(kind, message, Some(location));
+        Ok(())
+    })?;
+
+    let has_error = !e.is_empty() && e.len() == x.len();
+    Ok(ParsedPattern {
+        x,
+        y,
+        e: if has_error { Some(e) } else { None },
+        attenuation: None,
+        metadata: Metadata::default(),
+        warnings: warnings.into_vec(),
+    })
+}
+
+/// Parses standard XY files (two or three columns: x, y, [e]).
+///
+/// Ignores lines starting with one of the comment prefixes (`#` and `!` by
+/// default).
+pub fn parse_xy<R: Read>(reader: R, options: &ReadOptions) -> Result<ParsedPattern, Error> {
+    parse_text_columns(reader, "XY data", options, |line| {
+        line.split_whitespace().collect()
+    })
+}
+
+/// Parses CSV files.
+///
+/// Supports comma or whitespace as delimiters and skips comment lines like
+/// [`parse_xy`].
+pub fn parse_csv<R: Read>(reader: R, options: &ReadOptions) -> Result<ParsedPattern, Error> {
+    // Support both comma-separated and whitespace-separated CSV-like files.
+    parse_text_columns(reader, "CSV data", options, |line| {
+        line.split(|c: char| c == ',' || c.is_whitespace())
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .collect()
+    })
+}
+
+/// Parses Rigaku RASX files (zipped XML/text format).
+///
+/// Looks for a `Profile*.txt` file inside the archive and, when present, reads
+/// the matching `MesurementConditions*.xml` and `root.xml` for metadata.
+/// Intensities are returned as raw counts, with the third profile column kept
+/// as per-point attenuation factors.
+///
+/// The data set is chosen by [`ReadOptions::rasx_data_set`].
+pub fn parse_rasx<R: Read + Seek>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<ParsedPattern, Error> {
+    let mut archive = ZipArchive::new(reader)?;
+    let names = zip_entry_names(&mut archive);
+
+    let profile_name = match options.rasx_data_set {
+        Some(index) => names
+            .iter()
+            .filter(|n| is_rasx_profile(n))
+            .nth(index)
+            .ok_or_else(|| {
+                Error::FileNotFoundInArchive(format!("Profile*.txt (data set {index})"))
+            })?,
+        // Prioritize Data0/Profile0.txt, or find any Profile*.txt
+        None => names
+            .iter()
+            .find(|n| n.as_str() == "Data0/Profile0.txt")
+            .or_else(|| names.iter().find(|n| is_rasx_profile(n)))
+            .ok_or_else(|| Error::FileNotFoundInArchive("Profile*.txt".to_string()))?,
+    };
+
+    read_rasx_data_set(&mut archive, &names, profile_name, options)
+}
+
+/// Parses every data set (`Profile*.txt`) of a RASX archive, in archive order.
+///
+/// Each data set carries the metadata of its own `MesurementConditions*.xml`.
+/// [`ReadOptions::rasx_data_set`] is ignored.
+pub fn parse_rasx_all<R: Read + Seek>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<Vec<ParsedPattern>, Error> {
+    let mut archive = ZipArchive::new(reader)?;
+    let names = zip_entry_names(&mut archive);
+
+    let profiles: Vec<&String> = names.iter().filter(|n| is_rasx_profile(n)).collect();
+    if profiles.is_empty() {
+        return Err(Error::FileNotFoundInArchive("Profile*.txt".to_string()));
+    }
+
+    profiles
+        .into_iter()
+        .map(|name| read_rasx_data_set(&mut archive, &names, name, options))
+        .collect()
+}
+
+/// Reads a UTF-8 text entry of a zip archive.
+fn read_zip_text<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<String, Error> {
+    let mut text = String::new();
+    archive.by_name(name)?.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Lists the entry names of a zip archive, in archive order.
+fn zip_entry_names<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Vec<String> {
+    (0..archive.len())
+        .filter_map(|i| archive.by_index(i).ok().map(|f| f.name().to_string()))
+        .collect()
+}
+
+pub(crate) fn is_rasx_profile(name: &str) -> bool {
+    name.contains("Profile") && name.ends_with(".txt")
+}
+
+/// Reads one RASX profile together with its measurement conditions.
+///
+/// Comment lines are skipped like in [`parse_xy`]. Lines without a numeric
+/// (x, y) pair are skipped and invalid attenuation factors read as 1, both
+/// with a warning; with [`ReadOptions::strict`] they fail instead. Measurement
+/// conditions or a `root.xml` that cannot be read are skipped with a warning.
+fn read_rasx_data_set<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    names: &[String],
+    profile_name: &str,
+    options: &ReadOptions,
+) -> Result<ParsedPattern, Error> {
+    let mut warnings = WarningLog::default();
+    let text = read_text(archive.by_name(profile_name)?, profile_name, &mut warnings)?;
+
+    let mut x = Vec::new();
+    let mut y = Vec::new();
+    // Third column: attenuator/correction factor. Lines without it count as 1.
+    let mut factors = Vec::new();
+    let mut has_factors = false;
+
+    for_each_line(&text, |number, offset, line| {
+        let line = line.trim();
+        if line.is_empty() || is_comment(line, options) {
+            return Ok(());
+        }
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let location = || Location {
+            line: number,
+            offset,
+            text: line.to_string(),
+        };
+        let point = match parts[..] {
+            [x, y, ..] => x.parse::<f64>().ok().zip(y.parse::<f64>().ok()),
+            _ => None,
+        };
+        let Some((val_x, val_y)) = point else {
+            let message = "expected numeric x and y columns";
+            if options.strict {
+                return Err(Error::parse_at(message, location()));
+            }
+            warnings.add(WarningKind::SkippedLine, message, Some(location()));
+            return Ok(());
+        };
+        let factor = match parts.get(2).map(|p| p.parse::<f64>()) {
+            Some(Ok(factor)) => Some(factor),
+            Some(Err(_)) => {
+                let message = "invalid attenuation factor column";
+                if options.strict {
+                    return Err(Error::parse_at(message, location()));
+                }
+                warnings.add(WarningKind::InvalidValue, message, Some(location()));
+                None
+            }
+            None => None,
+        };
+        x.push(val_x);
+        y.push(val_y);
+        has_factors |= factor.is_some();
+        factors.push(factor.unwrap_or(1.0));
+        Ok(())
+    })?;
+
+    // Metadata is optional: unreadable companion files only cost a warning.
+    let conditions_name = rasx_conditions_name(profile_name);
+    let mut metadata = Metadata::default();
+    if names.contains(&conditions_name) {
+        match read_zip_text(archive, &conditions_name).and_then(|t| parse_rasx_conditions(&t)) {
+            Ok(conditions) => metadata = conditions,
+            Err(err) => warnings.add(
+                WarningKind::InvalidMetadata,
+                format!("{conditions_name} skipped: {err}"),
+                None,
+            ),
+        }
+    }
+    if names.iter().any(|n| n == "root.xml") {
+        match read_zip_text(archive, "root.xml").and_then(|t| parse_rasx_root_version(&t)) {
+            Ok(Some(version)) => {
+                metadata.extra.insert("RootVersion".to_string(), version);
+            }
+            Ok(None) => {}
+            Err(err) => warnings.add(
+                WarningKind::InvalidMetadata,
+                format!("root.xml skipped: {err}"),
+                None,
+            ),
+        }
+    }
+    metadata
+        .extra
+        .insert("Profile".to_string(), profile_name.to_string());
+
+    Ok(ParsedPattern {
+        x,
+        y,
+        e: None,
+        attenuation: if has_factors { Some(factors) } else { None },
+        metadata,
+        warnings: warnings.into_vec(),
+    })
+}
+
+/// Maps `DataN/ProfileN.txt` to its companion `DataN/MesurementConditionsN.xml`.
+///
+/// The misspelling of "Measurement" matches the entry names Rigaku writes.
+fn rasx_conditions_name(profile_name: &str) -> String {
+    let (dir, file) = match profile_name.rsplit_once('/') {
+        Some((dir, file)) => (format!("{dir}/"), file),
+        None => (String::new(), profile_name),
+    };
+    let index = file
+        .strip_prefix("Profile")
+        .and_then(|rest| rest.strip_suffix(".txt"))
+        .unwrap_or("0");
+    format!("{dir}MesurementConditions{index}.xml")
+}
+
+/// Extracts metadata from a RASX `MesurementConditions*.xml` document.
+///
+/// Besides the general information (operator, sample, system name), this
+/// records the X-ray generator, the selected hardware units (optics, slits,
+/// detector), the axis positions and the scan settings.
+fn parse_rasx_conditions(text: &str) -> Result<Metadata, Error> {
+    let mut xml = Reader::from_str(text.trim_start_matches('\u{feff}'));
+    xml.config_mut().trim_text(true);
+
+    let mut metadata = Metadata::default();
+    let mut conditions = MeasurementConditions::default();
+    let mut scan = Scan::default();
+    let mut path: Vec<String> = Vec::new();
+
+    loop {
+        match xml.read_event() {
+            Ok(Event::Start(e)) => {
+                path.push(String::from_utf8_lossy(e.local_name().as_ref()).into_owned());
+            }
+            Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"Category" => {
+                    if let (Some(name), Some(unit)) =
+                        (xml_attr(&e, b"Name")?, xml_attr(&e, b"SelectedUnit")?)
+                    {
+                        if name == "Detector" {
+                            conditions.detector = Some(unit.clone());
+                        }
+                        conditions.hardware.insert(name, unit);
+                    }
+                }
+                b"Axis" => {
+                    if let Some(name) = xml_attr(&e, b"Name")? {
+                        conditions.axes.push(AxisSetting {
+                            name,
+                            unit: xml_attr(&e, b"Unit")?.filter(|s| !s.is_empty()),
+                            position: xml_attr(&e, b"Position")?.filter(|s| !s.is_empty()),
+                            offset: xml_attr(&e, b"Offset")?.and_then(|s| s.parse().ok()),
+                            state: xml_attr(&e, b"State")?.filter(|s| !s.is_empty()),
+                        });
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::End(_)) => {
+                path.pop();
+            }
+            Ok(Event::Text(e)) => {
+                let text = e
+                    .decode()
+                    .map_err(|err| Error::parse(format!("RASX text decode error: {err}")))?;
+                let text = text.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                let (parent, name) = match path.as_slice() {
+                    [.., parent, name] => (parent.as_str(), name.as_str()),
+                    _ => continue,
+                };
+                match (parent, name) {
+                    ("GeneralInformation", "Operator") => {
+                        metadata.operator = Some(text.to_string())
+                    }
+                    ("GeneralInformation", "SampleName") => {
+                        metadata.sample_name = Some(text.to_string())
+                    }
+                    ("GeneralInformation", "SystemName") => {
+                        metadata.instrument = Some(text.to_string())
+                    }
+                    ("GeneralInformation", "Comment") => metadata.title = Some(text.to_string()),
+                    ("GeneralInformation", _) => {
+                        metadata.extra.insert(name.to_string(), text.to_string());
+                    }
+                    ("XrayGenerator", _) => {
+                        let generator = conditions.generator.get_or_insert_with(Default::default);
+                        match name {
+                            "TargetName" => {
+                                metadata.anode = Some(text.to_string());
+                                generator.target = Some(text.to_string());
+                            }
+                            "Voltage" => generator.voltage = text.parse().ok(),
+                            "Current" => generator.current = text.parse().ok(),
+                            "FocusSize" => generator.focus = Some(text.to_string()),
+                            "WavelengthKalpha1" => metadata.wavelength = text.parse().ok(),
+                            _ => {}
+                        }
+                    }
+                    ("Optics", "Name") => conditions.optics = Some(text.to_string()),
+                    ("Optics", "Attribute") => conditions.geometry = Some(text.to_string()),
+                    ("ScanInformation", _) => match name {
+                        "AxisName" => scan.axis = Some(text.to_string()),
+                        "Mode" => scan.mode = Some(text.to_string()),
+                        "Start" => scan.start = text.parse().ok(),
+                        "Stop" => scan.end = text.parse().ok(),
+                        "Step" => scan.step = text.parse().ok(),
+                        "Speed" => scan.speed = text.parse().ok(),
+                        "SpeedUnit" => scan.speed_unit = Some(text.to_string()),
+                        "PositionUnit" => scan.position_unit = Some(text.to_string()),
+                        "IntensityUnit" => scan.intensity_unit = Some(text.to_string()),
+                        "StartTime" => metadata.start_time = Some(text.to_string()),
+                        "EndTime" => metadata.end_time = Some(text.to_string()),
+                        _ => {}
+                    },
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(err) => {
+                return Err(Error::parse(format!("RASX conditions parse error: {err}")));
+            }
+            _ => {}
+        }
+    }
+
+    // Continuous scans record a speed rather than a dwell time per step.
+    if let (Some(step), Some(speed), Some("deg/min")) =
+        (scan.step, scan.speed, scan.speed_unit.as_deref())
+    {
+        if speed > 0.0 {
+            metadata.counting_time = Some(step / speed * 60.0);
+        }
+    }
+
+    if scan != Scan::default() {
+        metadata.scan = Some(scan);
+    }
+    if !conditions.is_empty() {
+        metadata.conditions = Some(conditions);
+    }
+
+    Ok(metadata)
+}
+
+/// Reads the format version from a RASX `root.xml` document.
+fn parse_rasx_root_version(text: &str) -> Result<Option<String>, Error> {
+    let mut xml = Reader::from_str(text.trim_start_matches('\u{feff}'));
+    loop {
+        match xml.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"Root" => {
+                return xml_attr(&e, b"Version");
+            }
+            Ok(Event::Eof) => return Ok(None),
+            Err(err) => return Err(Error::parse(format!("RASX root parse error: {err}"))),
+            _ => {}
+        }
+    }
+}
+
+/// Parses Panalytical XRDML files (XML-based).
+///
+/// Returns the first scan of the file; see [`parse_xrdml_all`]. x is taken
+/// from the `positions` of [`ReadOptions::xrdml_axis`], or of the axis named
+/// by each scan's `scanAxis`, either as explicit `listPositions` or spaced
+/// evenly from the start to the end position.
+///
+/// y comes from `<intensities>` or, in newer files, `<counts>`. e is the
+/// Poisson error of the raw counts. With
+/// [`ReadOptions::xrdml_counts_per_second`] both are divided by the
+/// `commonCountingTime`. `<beamAttenuationFactors>` are kept as per-point
+/// attenuation factors. An intensity that is not a number is kept as NaN with
+/// a warning, or fails with [`ReadOptions::strict`].
+pub fn parse_xrdml<R: Read>(reader: R, options: &ReadOptions) -> Result<ParsedPattern, Error> {
+    parse_xrdml_scans(reader, options, Some(1))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::parse("XRDML intensities not found"))
+}
+
+/// Parses every `<scan>` of every `<xrdMeasurement>` in an XRDML file.
+///
+/// Each pattern carries the sample and measurement metadata it was recorded
+/// with, plus its own `appendNumber`, scan mode and status.
+pub fn parse_xrdml_all<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<Vec<ParsedPattern>, Error> {
+    parse_xrdml_scans(reader, options, None)
+}
+
+/// One scan of an XRDML reciprocal space map.
+pub struct XrdmlMapScan {
+    /// Omega at every point.
+    pub omega: Vec<f64>,
+    /// 2Theta at every point.
+    pub two_theta: Vec<f64>,
+    /// The scan as a pattern, x taken from its scan axis.
+    pub data: ParsedPattern,
+}
+
+/// Parses every `<scan>` of an XRDML file with the Omega and 2Theta of each
+/// point, for assembling a reciprocal space map.
+pub fn parse_xrdml_map<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<Vec<XrdmlMapScan>, Error> {
+    read_xrdml_scans(reader, options, None)?
+        .into_iter()
+        .map(|scan| {
+            Ok(XrdmlMapScan {
+                omega: scan.axis_values("Omega")?,
+                two_theta: scan.axis_values("2Theta")?,
+                data: scan.into_pattern(options.xrdml_counts_per_second)?,
+            })
+        })
+        .collect()
+}
+
+/// Data collected for a single XRDML `<scan>` element.
+struct XrdmlScan {
+    metadata: Metadata,
+    /// Axis that x is taken from.
+    axis: String,
+    warnings: WarningLog,
+    /// `positions` of every axis, in file order.
+    positions: Vec<XrdmlPositions>,
+    intensities: Vec<f64>,
+    /// `beamAttenuationFactors`, one per intensity.
+    attenuation: Vec<f64>,
+}
+
+impl XrdmlScan {
+    fn new(mut metadata: Metadata, axis: String) -> Self {
+        if let Some(scan) = metadata.scan.as_mut() {
+            scan.x_axis = Some(axis.clone());
+        }
+        XrdmlScan {
+            metadata,
+            axis,
+            warnings: WarningLog::default(),
+            positions: Vec::new(),
+            intensities: Vec::new(),
+            attenuation: Vec::new(),
+        }
+    }
+
+    /// Returns the position of `axis` at every intensity.
+    fn axis_values(&self, axis: &str) -> Result<Vec<f64>, Error> {
+        self.positions
+            .iter()
+            .find(|positions| positions.axis == axis)
+            .ok_or_else(|| Error::parse(format!("XRDML missing {axis} positions")))?
+            .values(self.intensities.len())
+    }
+
+    /// Builds the pattern, taking x from the positions of `self.axis`.
+    fn into_pattern(self, counts_per_second: bool) -> Result<ParsedPattern, Error> {
+        let x = self.axis_values(&self.axis)?;
+        let XrdmlScan {
+            mut metadata,
+            axis,
+            warnings,
+            positions,
+            intensities,
+            attenuation,
+        } = self;
+        if !attenuation.is_empty() && attenuation.len() != intensities.len() {
+            return Err(Error::parse(format!(
+                "XRDML has {} beam attenuation factors for {} intensities",
+                attenuation.len(),
+                intensities.len()
+            )));
+        }
+        if let Some(scan) = metadata.scan.as_mut() {
+            scan.position_unit = positions
+                .into_iter()
+                .find(|positions| positions.axis == axis)
+                .and_then(|positions| positions.unit);
+        }
+
+        let (y, e) = xrdml_rates(intensities, &mut metadata, counts_per_second)?;
+        Ok(ParsedPattern {
+            x,
+            y,
+            e,
+            attenuation: (!attenuation.is_empty()).then_some(attenuation),
+            metadata,
+            warnings: warnings.into_vec(),
+        })
+    }
+}
+
+/// The `positions` of one axis within an XRDML scan.
+#[derive(Default)]
+struct XrdmlPositions {
+    axis: String,
+    unit: Option<String>,
+    start: Option<f64>,
+    end: Option<f64>,
+    common: Option<f64>,
+    /// Explicit `listPositions`, used instead of `start`/`end` when present.
+    list: Vec<f64>,
+}
+
+/// The child of `<positions>` whose text is being read.
+#[derive(Debug, Clone, Copy)]
+enum XrdmlPositionField {
+    Start,
+    End,
+    Common,
+    List,
+}
+
+impl XrdmlPositions {
+    /// Records the text of a `field` element.
+    fn read(&mut self, field: XrdmlPositionField, text: &str) -> Result<(), Error> {
+        let axis = &self.axis;
+        let parse = |value: &str, what: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|_| Error::parse(format!("XRDML invalid {axis} {what}")))
+        };
+        match field {
+            XrdmlPositionField::Start => self.start = Some(parse(text, "start position")?),
+            XrdmlPositionField::End => self.end = Some(parse(text, "end position")?),
+            XrdmlPositionField::Common => self.common = Some(parse(text, "common position")?),
+            XrdmlPositionField::List => {
+                for part in text.split_whitespace() {
+                    self.list
+                        .push(parse(part, &format!("list position {part:?}"))?);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the position of each of `count` points.
+    ///
+    /// Explicit `listPositions` come first; otherwise the points are spaced
+    /// evenly from the start to the end position, or all sit at the common
+    /// position.
+    fn values(&self, count: usize) -> Result<Vec<f64>, Error> {
+        let axis = &self.axis;
+        if !self.list.is_empty() {
+            if self.list.len() != count {
+                return Err(Error::parse(format!(
+                    "XRDML has {} {axis} list positions for {count} intensities",
+                    self.list.len()
+                )));
+            }
+            return Ok(self.list.clone());
+        }
+        match (self.start, self.end, self.common) {
+            (Some(start), Some(end), _) => Ok(linear_positions(start, end, count)),
+            (None, None, Some(common)) => Ok(vec![common; count]),
+            (None, _, _) => Err(Error::parse(format!("XRDML missing {axis} start position"))),
+            (Some(_), None, _) => Err(Error::parse(format!("XRDML missing {axis} end position"))),
+        }
+    }
+}
+
+/// Derives Poisson errors for XRDML intensities and, if `counts_per_second`
+/// is set, converts both to counts per second.
+///
+/// Intensities are in the unit recorded in `metadata.scan`: raw `counts`
+/// (the default) or `cps`, which the counting time turns back into counts.
+/// Errors are left out for other units.
+fn xrdml_rates(
+    mut y: Vec<f64>,
+    metadata: &mut Metadata,
+    counts_per_second: bool,
+) -> Result<(Vec<f64>, Option<Vec<f64>>), Error> {
+    let seconds = metadata.counting_time.filter(|time| *time > 0.0);
+    let unit = metadata
+        .scan
+        .as_ref()
+        .and_then(|scan| scan.intensity_unit.as_deref());
+    let is_cps = unit == Some("cps");
+    // Factor that turns the stored intensities into raw counts.
+    let to_counts = match unit {
+        None | Some("counts") => Some(1.0),
+        Some("cps") => seconds,
+        Some(_) => None,
+    };
+    let mut e: Option<Vec<f64>> = to_counts.map(|factor| {
+        y.iter()
+            .map(|value| (value * factor).max(0.0).sqrt() / factor)
+            .collect()
+    });
+
+    if counts_per_second && !is_cps {
+        let seconds = seconds.ok_or_else(|| {
+            Error::parse("XRDML counting time not found, cannot convert to counts per second")
+        })?;
+        for value in y.iter_mut().chain(e.iter_mut().flatten()) {
+            *value /= seconds;
+        }
+        metadata
+            .scan
+            .get_or_insert_with(Scan::default)
+            .intensity_unit = Some("cps".to_string());
+    }
+    Ok((y, e))
+}
+
+/// Returns `count` evenly spaced positions from `start` to `end`.
+fn linear_positions(start: f64, end: f64, count: usize) -> Vec<f64> {
+    if count == 1 {
+        return vec![start];
+    }
+    let step = (end - start) / (count as f64 - 1.0);
+    (0..count).map(|i| start + (i as f64) * step).collect()
+}
+
+/// Returns the `positions` axis that x is taken from for a scan whose
+/// `scanAxis` is `scan_axis`.
+///
+/// Coupled scans name their stepped axis first, so `Omega-2Theta` gives
+/// `Omega` and `2Theta-Omega` gives `2Theta`. `Gonio`, the coupled scan of
+/// powder measurements, and scans without a `scanAxis` give `2Theta`.
+fn xrdml_scan_x_axis(scan_axis: Option<&str>) -> String {
+    match scan_axis {
+        None | Some("Gonio") => "2Theta".to_string(),
+        Some(axis) => axis.split('-').next().unwrap_or(axis).to_string(),
+    }
+}
+
+/// Parses up to `limit` scans of an XRDML document into patterns, taking x
+/// from the `positions` of [`ReadOptions::xrdml_axis`], or of the scan's own
+/// axis when it is not set.
+fn parse_xrdml_scans<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+    limit: Option<usize>,
+) -> Result<Vec<ParsedPattern>, Error> {
+    read_xrdml_scans(reader, options, limit)?
+        .into_iter()
+        .map(|scan| scan.into_pattern(options.xrdml_counts_per_second))
+        .collect()
+}
+
+/// Walks an XRDML document and collects up to `limit` scans that hold
+/// intensities.
+///
+/// Metadata is inherited from the document (sample), then the enclosing
+/// `<xrdMeasurement>` (tube, wavelength, optics) and finally the scan header.
+fn read_xrdml_scans<R: Read>(
+    mut reader: R,
+    options: &ReadOptions,
+    limit: Option<usize>,
+) -> Result<Vec<XrdmlScan>, Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let mut lines = LineIndex::new(&bytes);
+    let mut xml = Reader::from_reader(bytes.as_slice());
+    xml.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut document = Metadata::default();
+    let mut measurement: Option<Metadata> = None;
+    let mut scan: Option<XrdmlScan> = None;
+    let mut scans = Vec::new();
+    let mut in_intensities = false;
+    let mut in_positions = false;
+    let mut position_field: Option<XrdmlPositionField> = None;
+    let mut capture_factors = false;
+
+    loop {
+        let event_start = xml.buffer_position() as usize;
+        match xml.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                match e.local_name().as_ref() {
+                    b"xrdMeasurement" => {
+                        measurement = Some(document.clone());
+                    }
+                    b"scan" => {
+                        let mut metadata = measurement.clone().unwrap_or_else(|| document.clone());
+                        let scan_axis = xml_attr(&e, b"scanAxis")?;
+                        let x_axis = options
+                            .xrdml_axis
+                            .clone()
+                            .unwrap_or_else(|| xrdml_scan_x_axis(scan_axis.as_deref()));
+                        metadata.scan = Some(Scan {
+                            index: xml_attr(&e, b"appendNumber")?.and_then(|v| v.parse().ok()),
+                            axis: scan_axis,
+                            mode: xml_attr(&e, b"mode")?,
+                            status: xml_attr(&e, b"status")?,
+                            ..Scan::default()
+                        });
+                        scan = Some(XrdmlScan::new(metadata, x_axis));
+                    }
+                    b"positions" => {
+                        if let Some(scan) = scan.as_mut() {
+                            scan.positions.push(XrdmlPositions {
+                                axis: xml_attr(&e, b"axis")?.unwrap_or_default(),
+                                unit: xml_attr(&e, b"unit")?,
+                                ..XrdmlPositions::default()
+                            });
+                            in_positions = true;
+                        }
+                    }
+                    b"startPosition" if in_positions => {
+                        position_field = Some(XrdmlPositionField::Start);
+                    }
+                    b"endPosition" if in_positions => {
+                        position_field = Some(XrdmlPositionField::End);
+                    }
+                    b"commonPosition" if in_positions => {
+                        position_field = Some(XrdmlPositionField::Common);
+                    }
+                    b"listPositions" if in_positions => {
+                        position_field = Some(XrdmlPositionField::List);
+                    }
+                    b"beamAttenuationFactors" => {
+                        capture_factors = true;
+                    }
+                    b"intensities" | b"counts" => {
+                        in_intensities = true;
+                        if let Some(info) = scan.as_mut().and_then(|s| s.metadata.scan.as_mut()) {
+                            info.intensity_unit = xml_attr(&e, b"unit")?;
+                        }
+                    }
+                    b"usedWavelength" => {
+                        let metadata =
+                            current_xrdml_metadata(&mut document, &mut measurement, &mut scan);
+                        metadata.radiation = Some(Radiation {
+                            intended: xml_attr(&e, b"intended")?,
+                            ..Radiation::default()
+                        });
+                    }
+                    b"kAlpha1" | b"kAlpha2" | b"kBeta" => {
+                        let metadata =
+                            current_xrdml_metadata(&mut document, &mut measurement, &mut scan);
+                        if let Some(radiation) = metadata.radiation.as_mut() {
+                            if radiation.unit.is_none() {
+                                radiation.unit = xml_attr(&e, b"unit")?;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                path.push(String::from_utf8_lossy(e.local_name().as_ref()).into_owned());
+            }
+            Ok(Event::Text(e)) => {
+                let text = e
+                    .decode()
+                    .map_err(|err| Error::parse(format!("XRDML text decode error: {err}")))?;
+                let text = text.trim();
+                let positions = scan.as_mut().and_then(|scan| scan.positions.last_mut());
+                if text.is_empty() {
+                    // Skip empty text nodes.
+                } else if let (Some(field), Some(positions)) = (position_field, positions) {
+                    positions.read(field, text)?;
+                } else if let (true, Some(scan)) = (capture_factors, scan.as_mut()) {
+                    for part in text.split_whitespace() {
+                        scan.attenuation.push(part.parse::<f64>().map_err(|_| {
+                            Error::parse(format!("XRDML invalid beam attenuation factor {part:?}"))
+                        })?);
+                    }
+                } else if let (true, Some(scan)) = (in_intensities, scan.as_mut()) {
+                    // Tokens are located in the raw text so errors can point at them.
+                    let raw = &bytes[event_start..xml.buffer_position() as usize];
+                    for (offset, part) in ascii_tokens(raw) {
+                        let part = String::from_utf8_lossy(part);
+                        if let Ok(value) = part.parse::<f64>() {
+                            scan.intensities.push(value);
+                            continue;
+                        }
+                        let message = format!("XRDML intensity {part:?} is not a number");
+                        let location = lines.locate(event_start + offset, &part);
+                        if options.strict {
+                            return Err(Error::parse_at(message, location));
+                        }
+                        // Keep the point so x stays aligned with y.
+                        scan.intensities.push(f64::NAN);
+                        scan.warnings
+                            .add(WarningKind::InvalidValue, message, Some(location));
+                    }
+                } else {
+                    let metadata =
+                        current_xrdml_metadata(&mut document, &mut measurement, &mut scan);
+                    apply_xrdml_metadata(&path, text, metadata);
+                }
+            }
+            Ok(Event::End(e)) => {
+                path.pop();
+                match e.local_name().as_ref() {
+                    b"positions" => {
+                        in_positions = false;
+                    }
+                    b"startPosition" | b"endPosition" | b"commonPosition" | b"listPositions" => {
+                        position_field = None;
+                    }
+                    b"beamAttenuationFactors" => {
+                        capture_factors = false;
+                    }
+                    b"intensities" | b"counts" => {
+                        in_intensities = false;
+                    }
+                    b"scan" => {
+                        if let Some(mut scan) = scan.take().filter(|s| !s.intensities.is_empty()) {
+                            scan.metadata.wavelength = scan
+                                .metadata
+                                .radiation
+                                .as_ref()
+                                .and_then(Radiation::to_angstrom)
+                                .and_then(|radiation| radiation.k_alpha1);
+                            scans.push(scan);
+                            if limit.is_some_and(|limit| scans.len() >= limit) {
+                                break;
+                            }
+                        }
+                    }
+                    b"xrdMeasurement" => {
+                        measurement = None;
+                    }
+                    _ => {}
                 }
             }
+            Ok(Event::Eof) => break,
+            Err(err) => {
+                return Err(Error::parse(format!("XRDML parse error: {err}")));
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if scans.is_empty() {
+        return Err(Error::parse("XRDML intensities not found"));
+    }
+    Ok(scans)
+}
+
+/// Splits `bytes` at ASCII whitespace, up to the first `<`, returning each
+/// token with its offset.
+fn ascii_tokens(bytes: &[u8]) -> impl Iterator<Item = (usize, &[u8])> {
+    let end = bytes.iter().position(|&b| b == b'<').unwrap_or(bytes.len());
+    let mut offset = 0;
+    bytes[..end]
+        .split(u8::is_ascii_whitespace)
+        .filter_map(move |token| {
+            let start = offset;
+            offset += token.len() + 1;
+            (!token.is_empty()).then_some((start, token))
+        })
+}
+
+/// Maps byte offsets of an in-memory document to line [`Location`]s.
+///
+/// Offsets have to be looked up in increasing order.
+struct LineIndex<'a> {
+    bytes: &'a [u8],
+    scanned: usize,
+    line: usize,
+    line_start: usize,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        LineIndex {
+            bytes,
+            scanned: 0,
+            line: 1,
+            line_start: 0,
+        }
+    }
+
+    /// Returns the location of the line holding `offset`, with `text` as the
+    /// offending text.
+    fn locate(&mut self, offset: usize, text: &str) -> Location {
+        let offset = offset.min(self.bytes.len()).max(self.scanned);
+        for (index, &byte) in self.bytes[self.scanned..offset].iter().enumerate() {
+            if byte == b'\n' {
+                self.line += 1;
+                self.line_start = self.scanned + index + 1;
+            }
+        }
+        self.scanned = offset;
+        Location {
+            line: self.line,
+            offset: self.line_start as u64,
+            text: text.to_string(),
         }
     }
 }
 
-/// Parses standard XY files (two or three columns: x, y, [e]).
+/// Returns the metadata that header text at the current position belongs to.
+fn current_xrdml_metadata<'a>(
+    document: &'a mut Metadata,
+    measurement: &'a mut Option<Metadata>,
+    scan: &'a mut Option<XrdmlScan>,
+) -> &'a mut Metadata {
+    match (scan.as_mut(), measurement.as_mut()) {
+        (Some(scan), _) => &mut scan.metadata,
+        (None, Some(measurement)) => measurement,
+        (None, None) => document,
+    }
+}
+
+/// Records a header text node of an XRDML document into `metadata`.
+///
+/// `path` holds the local names of the enclosing elements, innermost last.
+fn apply_xrdml_metadata(path: &[String], text: &str, metadata: &mut Metadata) {
+    let (parent, name) = match path {
+        [.., parent, name] => (parent.as_str(), name.as_str()),
+        _ => return,
+    };
+    match (parent, name) {
+        ("sample", "name") => metadata.sample_name = Some(text.to_string()),
+        ("sample", "id") => {
+            metadata.sample_name.get_or_insert_with(|| text.to_string());
+        }
+        ("author", "name") => metadata.operator = Some(text.to_string()),
+        ("source", "instrumentControlSoftware") => metadata.instrument = Some(text.to_string()),
+        ("source", "instrumentID") | ("source", "applicationSoftware") => {
+            metadata.extra.insert(name.to_string(), text.to_string());
+        }
+        ("xRayTube", "anodeMaterial") => metadata.anode = Some(text.to_string()),
+        ("usedWavelength", _) => {
+            if let Some(radiation) = metadata.radiation.as_mut() {
+                let value = text.parse().ok();
+                match name {
+                    "kAlpha1" => radiation.k_alpha1 = value,
+                    "kAlpha2" => radiation.k_alpha2 = value,
+                    "kBeta" => radiation.k_beta = value,
+                    "ratioKAlpha2KAlpha1" => radiation.ratio_k_alpha2_k_alpha1 = value,
+                    _ => {}
+                }
+            }
+        }
+        ("incidentBeamPath", "radius") => metadata.goniometer_radius = text.parse().ok(),
+        ("dataPoints", "commonCountingTime") => metadata.counting_time = text.parse().ok(),
+        ("header", "startTimeStamp") => metadata.start_time = Some(text.to_string()),
+        ("header", "endTimeStamp") => metadata.end_time = Some(text.to_string()),
+        _ => {}
+    }
+}
+
+/// Returns the unescaped value of attribute `name` on an XML start tag.
+fn xml_attr(e: &BytesStart, name: &[u8]) -> Result<Option<String>, Error> {
+    for attr in e.attributes() {
+        let attr = attr.map_err(|err| Error::parse(format!("XML attribute error: {err}")))?;
+        if attr.key.as_ref() == name {
+            let value = attr
+                .unescape_value()
+                .map_err(|err| Error::parse(format!("XML attribute decode error: {err}")))?;
+            return Ok(Some(value.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// Parses GSAS RAW files.
+///
+/// Returns the first `BANK` of the file; see [`parse_gsas_raw_all`].
+pub fn parse_gsas_raw<R: Read>(reader: R, options: &ReadOptions) -> Result<ParsedPattern, Error> {
+    parse_gsas_raw_banks(reader, options, Some(1))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::parse("BANK header not found in RAW file"))
+}
+
+/// Parses every `BANK` of a GSAS RAW file, in file order.
+///
+/// The first line of the file is kept as the title of every bank; each bank
+/// carries its own header parameters in `metadata.bank`.
+pub fn parse_gsas_raw_all<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<Vec<ParsedPattern>, Error> {
+    parse_gsas_raw_banks(reader, options, None)
+}
+
+/// A `TIME_MAP` record: clock width in ns and the raw map values.
+struct GsasTimeMap {
+    clock_width: f64,
+    values: Vec<f64>,
+}
+
+/// Splits a GSAS RAW file into banks and decodes up to `limit` of them.
+///
+/// `TIME_MAP` records may appear anywhere in the file, so the whole file is
+/// read before any bank is decoded.
+fn parse_gsas_raw_banks<R: Read>(
+    reader: R,
+    options: &ReadOptions,
+    limit: Option<usize>,
+) -> Result<Vec<ParsedPattern>, Error> {
+    let mut warnings = WarningLog::default();
+    let text = read_text(reader, "GSAS data", &mut warnings)?;
+    let mut metadata = Metadata::default();
+    let mut banks: Vec<(BankHeader, Vec<TextLine>)> = Vec::new();
+    let mut time_maps: HashMap<u32, GsasTimeMap> = HashMap::new();
+    let mut current_map: Option<u32> = None;
+
+    for text_line in text_lines(&text) {
+        let line = text_line.text;
+        if line.starts_with("BANK") {
+            banks.push((parse_gsas_bank_header(line)?, Vec::new()));
+            current_map = None;
+        } else if line.starts_with("TIME_MAP") {
+            // TIME_MAP IMAP NVAL NREC TIME_MAP CLCKWDT
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let (Some(id), Some(clock_width)) = (
+                parts.get(1).and_then(|v| v.parse::<u32>().ok()),
+                parts.get(5).and_then(|v| v.parse::<f64>().ok()),
+            ) else {
+                return Err(Error::parse(format!("Invalid TIME_MAP header: {line}")));
+            };
+            time_maps.insert(
+                id,
+                GsasTimeMap {
+                    clock_width,
+                    values: Vec::new(),
+                },
+            );
+            current_map = Some(id);
+        } else if text_line.number == 1 && banks.is_empty() {
+            let title = line.trim();
+            if !title.is_empty() {
+                metadata.title = Some(title.to_string());
+            }
+        } else if line.starts_with('#') {
+            continue;
+        } else if let Some(map) = current_map.and_then(|id| time_maps.get_mut(&id)) {
+            map.values.extend(
+                line.split_whitespace()
+                    .filter_map(|v| v.parse::<f64>().ok()),
+            );
+        } else if let Some((_, lines)) = banks.last_mut() {
+            lines.push(text_line);
+        } else if line.starts_with("Instrument parameter") {
+            if let Some((key, value)) = line.split_once(':') {
+                metadata
+                    .extra
+                    .insert(key.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+
+    if banks.is_empty() {
+        return Err(Error::parse("BANK header not found in RAW file"));
+    }
+    banks
+        .into_iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|(header, lines)| {
+            let warnings = warnings.clone();
+            decode_gsas_bank(header, &lines, &time_maps, &metadata, options, warnings)
+        })
+        .collect()
+}
+
+/// GSAS data record types understood by [`decode_gsas_bank`].
+const GSAS_DATA_TYPES: [&str; 4] = ["STD", "ESD", "ALT", "FXYE"];
+
+/// Parses a `BANK` line: `BANK IBANK NCHAN NREC BINTYP BCOEF... [TYPE]`.
+///
+/// A missing data type defaults to `STD`; any other token than a number or
+/// one of [`GSAS_DATA_TYPES`] before it is an error.
+fn parse_gsas_bank_header(line: &str) -> Result<BankHeader, Error> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    // BANK 1 4941 494 CONST 1600.0 1.7 0.0 0.0 STD
+    if parts.len() < 5 {
+        return Err(Error::parse(format!("Invalid BANK header: {line}")));
+    }
+    let number = parts[1]
+        .parse::<u32>()
+        .map_err(|_| Error::parse("Invalid BANK number"))?;
+    let channels = parts[2]
+        .parse::<usize>()
+        .map_err(|_| Error::parse("Invalid BANK channel count"))?;
+    let records = parts[3]
+        .parse::<usize>()
+        .map_err(|_| Error::parse("Invalid BANK record count"))?;
+
+    let mut coefficients = Vec::new();
+    let mut data_type = None;
+    // Tokens after the data type are ignored.
+    for part in &parts[5..] {
+        match part.parse::<f64>() {
+            Ok(value) => coefficients.push(value),
+            Err(_) if GSAS_DATA_TYPES.contains(part) => {
+                data_type = Some(part.to_string());
+                break;
+            }
+            Err(_) => return Err(Error::parse(format!("Unsupported GSAS data type: {part}"))),
+        }
+    }
+
+    Ok(BankHeader {
+        number,
+        channels,
+        records,
+        binning: parts[4].to_string(),
+        coefficients,
+        data_type: data_type.unwrap_or_else(|| "STD".to_string()),
+    })
+}
+
+/// Decodes the data records of one bank into a pattern.
+///
+/// `STD` and `ESD` records hold intensities only and x is generated from the
+/// binning coefficients; `ALT` and `FXYE` records carry explicit x values.
+/// `CONST` positions are converted from centidegrees to degrees unless
+/// [`ReadOptions::gsas_centidegrees`] is off, `RALF` times from 1/32 µs to µs;
+/// other binnings are time-of-flight in µs.
+fn decode_gsas_bank(
+    header: BankHeader,
+    lines: &[TextLine],
+    time_maps: &HashMap<u32, GsasTimeMap>,
+    file_metadata: &Metadata,
+    options: &ReadOptions,
+    mut warnings: WarningLog,
+) -> Result<ParsedPattern, Error> {
+    let scale = match header.binning.as_str() {
+        "CONST" if options.gsas_centidegrees => 100.0,
+        "CONST" => 1.0,
+        "RALF" => 32.0,
+        "SLOG" | "LOG6" | "TIME_MAP" => 1.0,
+        other => {
+            return Err(Error::parse(format!(
+                "Unsupported GSAS binning type: {other}"
+            )))
+        }
+    };
+
+    let mut x = Vec::new();
+    let mut y = Vec::new();
+    let mut e = Vec::new();
+    // Reads fixed-width fields, keeping invalid ones as NaN so later fields
+    // keep their place.
+    let mut fields = |line: &TextLine, widths: &[usize]| -> Result<Vec<f64>, Error> {
+        let mut values = Vec::with_capacity(widths.len());
+        for value in fixed_width_fields(line.text, widths) {
+            let value = match value {
+                Some(value) => value,
+                None => {
+                    let message = "invalid fixed-width field in GSAS record";
+                    line_issue(
+                        options,
+                        &mut warnings,
+                        WarningKind::InvalidValue,
+                        message,
+                        line,
+                    )?;
+                    f64::NAN
+                }
+            };
+            values.push(value);
+        }
+        Ok(values)
+    };
+    match header.data_type.as_str() {
+        // 10 x (I2, F6.0): optional counter and intensity
+        "STD" => {
+            let mut counters = Vec::new();
+            let mut has_counter = false;
+            for line in lines {
+                has_counter |=
+                    read_gsas_std_line(line, &mut y, &mut counters, options, &mut warnings)?;
+            }
+            // Poisson variance of a value summed over NCTR counts is y / NCTR
+            if has_counter {
+                e = y
+                    .iter()
+                    .zip(&counters)
+                    .map(|(value, counter)| (value.max(0.0) / counter).sqrt())
+                    .collect();
+            }
+        }
+        // 10 x (F8.0): intensity and esd pairs
+        "ESD" => {
+            for line in lines {
+                for pair in fields(line, &[8; 10])?.chunks(2) {
+                    y.push(pair[0]);
+                    e.push(pair.get(1).copied().unwrap_or(0.0));
+                }
+            }
+        }
+        // 4 x (F8.0, F7.4, F5.4): position, intensity and esd
+        "ALT" => {
+            for line in lines {
+                let values = fields(line, &[8, 7, 5, 8, 7, 5, 8, 7, 5, 8, 7, 5])?;
+                for point in values.chunks(3) {
+                    x.push(point[0] / scale);
+                    y.push(point.get(1).copied().unwrap_or(0.0));
+                    e.push(point.get(2).copied().unwrap_or(0.0));
+                }
+            }
+        }
+        // Free-format position, intensity and esd, one point per line
+        "FXYE" => {
+            for line in lines {
+                if line.text.trim().is_empty() {
+                    continue;
+                }
+                let values: Result<Vec<f64>, _> = line
+                    .text
+                    .split_whitespace()
+                    .map(str::parse::<f64>)
+                    .collect();
+                match values.as_deref() {
+                    Ok([val_x, val_y, val_e, ..]) => {
+                        x.push(val_x / scale);
+                        y.push(*val_y);
+                        e.push(*val_e);
+                    }
+                    _ => {
+                        let message = "expected numeric x, y and e columns";
+                        line_issue(
+                            options,
+                            &mut warnings,
+                            WarningKind::SkippedLine,
+                            message,
+                            line,
+                        )?;
+                    }
+                }
+            }
+        }
+        other => return Err(Error::parse(format!("Unsupported GSAS data type: {other}"))),
+    }
+
+    if header.channels > 0 && y.len() > header.channels {
+        y.truncate(header.channels);
+        x.truncate(header.channels);
+        e.truncate(header.channels);
+    }
+    if x.is_empty() {
+        x = gsas_bin_positions(&header, y.len(), scale, time_maps)?;
+    }
+
+    let mut metadata = file_metadata.clone();
+    if header.binning != "CONST" {
+        metadata.scan = Some(Scan {
+            axis: Some("TOF".to_string()),
+            position_unit: Some("us".to_string()),
+            ..Scan::default()
+        });
+    }
+    metadata.bank = Some(header);
+    Ok(ParsedPattern {
+        x,
+        y,
+        e: if e.is_empty() { None } else { Some(e) },
+        attenuation: None,
+        metadata,
+        warnings: warnings.into_vec(),
+    })
+}
+
+/// Generates `count` bin positions from the binning coefficients of `header`.
+///
+/// `CONST` and `RALF` coefficients are divided by `scale`.
+fn gsas_bin_positions(
+    header: &BankHeader,
+    count: usize,
+    scale: f64,
+    time_maps: &HashMap<u32, GsasTimeMap>,
+) -> Result<Vec<f64>, Error> {
+    let coefficient = |index: usize| {
+        header.coefficients.get(index).copied().ok_or_else(|| {
+            Error::parse(format!(
+                "Missing BCOEF({}) for {} binning",
+                index + 1,
+                header.binning
+            ))
+        })
+    };
+    let geometric = |start: f64, ratio: f64| {
+        let mut x = Vec::with_capacity(count);
+        let mut value = start;
+        for _ in 0..count {
+            x.push(value);
+            value *= 1.0 + ratio;
+        }
+        x
+    };
+
+    match header.binning.as_str() {
+        // BCOEF(1) start, BCOEF(2) step, usually in centidegrees
+        "CONST" => {
+            let start = coefficient(0)? / scale;
+            let step = coefficient(1)? / scale;
+            Ok((0..count).map(|i| start + (i as f64) * step).collect())
+        }
+        // BCOEF(1) first TOF x 32, BCOEF(4) delta-t/t
+        "RALF" => Ok(geometric(coefficient(0)? / scale, coefficient(3)?)),
+        // BCOEF(1) first TOF, BCOEF(2) last TOF, BCOEF(3) delta-t/t
+        "SLOG" => Ok(geometric(coefficient(0)?, coefficient(2)?)),
+        // BCOEF(1) first TOF, BCOEF(2) delta-t/t
+        "LOG6" => Ok(geometric(coefficient(0)?, coefficient(1)?)),
+        // BCOEF(1) time map number
+        "TIME_MAP" => {
+            let id = coefficient(0)? as u32;
+            let map = time_maps
+                .get(&id)
+                .ok_or_else(|| Error::parse(format!("TIME_MAP {id} not found")))?;
+            let x = time_map_positions(map, count);
+            if x.len() < count {
+                return Err(Error::parse(format!(
+                    "TIME_MAP {id} covers {} of {count} channels",
+                    x.len()
+                )));
+            }
+            Ok(x)
+        }
+        other => Err(Error::parse(format!(
+            "Unsupported GSAS binning type: {other}"
+        ))),
+    }
+}
+
+/// Expands a `TIME_MAP` into the start time of each channel, in µs.
+///
+/// The map is a list of `(first channel, first time, time step)` triplets in
+/// clock ticks, terminated by the end time of the last channel.
+fn time_map_positions(map: &GsasTimeMap, count: usize) -> Vec<f64> {
+    let segments: Vec<&[f64]> = map.values.chunks_exact(3).collect();
+    let end_time = map.values.last().copied().unwrap_or(0.0);
+    let mut x = Vec::with_capacity(count);
+    for (index, segment) in segments.iter().enumerate() {
+        let (time, step) = (segment[1], segment[2]);
+        let next = segments.get(index + 1).map_or(end_time, |s| s[1]);
+        if step <= 0.0 {
+            break;
+        }
+        let mut tick = time;
+        while tick < next && x.len() < count {
+            x.push(tick * map.clock_width / 1000.0);
+            tick += step;
+        }
+    }
+    x
+}
+
+/// Reads one `STD` record of up to ten 8-column (I2, F6.0) fields.
+///
+/// Each field is an optional `NCTR` counter followed by the intensity; a blank
+/// counter counts as 1. A field whose counter and intensity columns are both
+/// full may also be a 7-digit intensity; it is read as counter and intensity
+/// with a warning, or fails in strict mode. Lines that do not follow the fixed layout are split on
+/// whitespace instead, where values that are not numbers are kept as NaN with
+/// a warning, or fail in strict mode. Returns `true` if any field carried a
+/// counter.
+fn read_gsas_std_line(
+    line: &TextLine,
+    y: &mut Vec<f64>,
+    counters: &mut Vec<f64>,
+    options: &ReadOptions,
+    warnings: &mut WarningLog,
+) -> Result<bool, Error> {
+    let text = line.text;
+    let mut values = Vec::with_capacity(10);
+    let mut has_counter = false;
+    let mut fixed = true;
+    let mut ambiguous = Vec::new();
+    for offset in (0..80).step_by(8) {
+        let Some(field) = text.get(offset..(offset + 8).min(text.len())) else {
+            break;
+        };
+        if field.trim().is_empty() {
+            continue;
+        }
+        let (Some(counter), Some(value)) = (field.get(..2), field.get(2..)) else {
+            fixed = false;
+            break;
+        };
+        let counter_text = counter.trim();
+        let counter = match counter_text {
+            "" => Some(1.0),
+            text => text.parse::<u32>().ok().map(|n| f64::from(n.max(1))),
+        };
+        if !counter_text.is_empty() && !value.starts_with(' ') && value.len() == 6 {
+            ambiguous.push(field);
+        }
+        match (counter, value.trim().parse::<f64>()) {
+            (Some(counter), Ok(value)) => {
+                has_counter |= !counter_text.is_empty();
+                values.push((value, counter));
+            }
+            _ => {
+                fixed = false;
+                break;
+            }
+        }
+    }
+
+    if fixed {
+        for field in ambiguous {
+            let message =
+                format!("GSAS STD field {field:?} is ambiguous: a counter or a 7-digit intensity");
+            line_issue(options, warnings, WarningKind::InvalidValue, message, line)?;
+        }
+    } else {
+        values.clear();
+        has_counter = false;
+        for part in text.split_whitespace() {
+            let value = match part.parse::<f64>() {
+                Ok(value) => value,
+                Err(_) => {
+                    let message = format!("GSAS intensity {part:?} is not a number");
+                    line_issue(options, warnings, WarningKind::InvalidValue, message, line)?;
+                    f64::NAN
+                }
+            };
+            values.push((value, 1.0));
+        }
+    }
+    for (value, counter) in values {
+        y.push(value);
+        counters.push(counter);
+    }
+    Ok(has_counter)
+}
+
+/// Splits `line` into Fortran F-format fields, one value per width.
+///
+/// A blank field reads as 0 so later fields keep their place; fields past the
+/// end of the line are not returned. Text that is not a number is `None`.
+fn fixed_width_fields(line: &str, widths: &[usize]) -> Vec<Option<f64>> {
+    let mut values = Vec::with_capacity(widths.len());
+    let mut offset = 0;
+    for width in widths {
+        if offset >= line.len() {
+            break;
+        }
+        let end = (offset + width).min(line.len());
+        let value = match line.get(offset..end).map(str::trim) {
+            Some("") => Some(0.0),
+            Some(field) => field.parse::<f64>().ok(),
+            None => None,
+        };
+        offset = end;
+        values.push(value);
+    }
+    values
+}
+
+/// Parses Bruker binary RAW files.
+///
+/// Returns the first range of the file; see [`parse_bruker_raw_all`].
+///
+/// The format version is taken from the magic bytes (`RAW `, `RAW2`,
+/// `RAW1.01`, `RAW4.00`) and each version is decoded from its block
+/// structure: file header, range headers and typed meta-records. Variants the
+/// structured parsers do not recognize fall back to heuristics that locate the
+/// intensity block and axis metadata.
+///
+/// Bruker RAW4 files are not fully documented and may store intensity points
+/// either as contiguous `f32` values or as interleaved records near the file
+/// tail (e.g. `f32 value` + `u32 status`).
+pub fn parse_bruker_raw<R: Read>(reader: R) -> Result<ParsedPattern, Error> {
+    parse_bruker_raw_ranges(reader, Some(1))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::parse("Bruker RAW file contains no range"))
+}
+
+/// Parses every range of a Bruker binary RAW file, in file order.
 ///
-/// Ignores lines starting with '#' or '!'.
-pub fn parse_xy<R: Read>(reader: R) -> Result<ParsedPattern, Error> {
-    let reader = BufReader::new(reader);
-    let mut x = Vec::new();
-    let mut y = Vec::new();
-    let mut e = Vec::new();
+/// Each range carries its own start, step and step time in
+/// `metadata.scan` and `metadata.counting_time`. Files that need the
+/// heuristic fallback yield a single pattern.
+pub fn parse_bruker_raw_all<R: Read>(reader: R) -> Result<Vec<ParsedPattern>, Error> {
+    parse_bruker_raw_ranges(reader, None)
+}
 
-    for line in reader.lines() {
-        let line = line?;
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
-            continue;
-        }
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        parse_columns(&parts, &mut x, &mut y, &mut e);
+/// Decodes up to `limit` ranges of a Bruker RAW file.
+fn parse_bruker_raw_ranges<R: Read>(
+    mut reader: R,
+    limit: Option<usize>,
+) -> Result<Vec<ParsedPattern>, Error> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
+
+    if !buf.starts_with(b"RAW") {
+        return Err(Error::parse("Unsupported Bruker RAW header"));
     }
 
-    let has_error = !e.is_empty() && e.len() == x.len();
-    Ok(ParsedPattern {
-        x,
-        y,
-        e: if has_error { Some(e) } else { None },
-    })
+    let (version, ranges, mut metadata) = read_bruker_raw_structure(&buf);
+    if !version.is_empty() {
+        metadata
+            .extra
+            .insert("FormatVersion".to_string(), version.to_string());
+    }
+
+    if ranges.is_empty() {
+        return parse_bruker_raw_heuristic(&buf, metadata).map(|pattern| vec![pattern]);
+    }
+    ranges
+        .iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .enumerate()
+        .map(|(index, range)| read_bruker_range(&buf, index, range, &metadata))
+        .collect()
 }
 
-/// Parses CSV files.
+/// Detects the RAW version from the magic bytes and decodes its structure.
 ///
-/// Supports comma or whitespace as delimiters.
-pub fn parse_csv<R: Read>(reader: R) -> Result<ParsedPattern, Error> {
-    let reader = BufReader::new(reader);
-    let mut x = Vec::new();
-    let mut y = Vec::new();
-    let mut e = Vec::new();
-
-    for line in reader.lines() {
-        let line = line?;
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
-            continue;
+/// Returns the version (empty if unknown), the ranges found and the
+/// file-level metadata.
+fn read_bruker_raw_structure(buf: &[u8]) -> (&'static str, Vec<BrukerRange>, Metadata) {
+    match buf.get(..4).unwrap_or_default() {
+        b"RAW4" => (
+            "4",
+            read_bruker_raw4_ranges(buf),
+            read_bruker_raw4_metadata(buf),
+        ),
+        // `RAW1.01`
+        b"RAW1" => {
+            let (ranges, metadata) = read_bruker_raw3(buf);
+            ("3", ranges, metadata)
         }
-        // Support both comma-separated and whitespace-separated CSV-like files.
-        let parts: Vec<&str> = line
-            .split(|c: char| c == ',' || c.is_whitespace())
-            .map(|p| p.trim())
-            .filter(|p| !p.is_empty())
-            .collect();
-        parse_columns(&parts, &mut x, &mut y, &mut e);
+        b"RAW2" => {
+            let (ranges, metadata) = read_bruker_raw2(buf);
+            ("2", ranges, metadata)
+        }
+        b"RAW " => {
+            let (ranges, metadata) = read_bruker_raw1(buf);
+            ("1", ranges, metadata)
+        }
+        _ => ("", Vec::new(), Metadata::default()),
     }
+}
 
-    let has_error = !e.is_empty() && e.len() == x.len();
-    Ok(ParsedPattern {
-        x,
-        y,
-        e: if has_error { Some(e) } else { None },
-    })
+/// A scan range located in a Bruker RAW file.
+#[derive(Debug, Clone, Default)]
+struct BrukerRange {
+    start: f64,
+    step: f64,
+    count: usize,
+    step_time: Option<f64>,
+    scan_type: Option<String>,
+    voltage: Option<f64>,
+    current: Option<f64>,
+    wavelength: Option<f64>,
+    detector: Option<String>,
+    axes: Vec<AxisSetting>,
+    /// Offsets of the start and step values in the range header.
+    start_offset: usize,
+    step_offset: usize,
+    /// Offset of the first data point.
+    data_offset: usize,
+    /// Bytes per data point; the `f32` intensity comes first.
+    datum_size: usize,
 }
 
-/// Parses Rigaku RASX files (zipped XML/text format).
+/// Walks the RAW4 range headers that follow the file-level records.
 ///
-/// Looks for a `Profile*.txt` file inside the archive.
-pub fn parse_rasx<R: Read + Seek>(reader: R) -> Result<ParsedPattern, Error> {
-    let mut archive = ZipArchive::new(reader)?;
+/// Each range is a 160-byte header, a run of supplementary records (detector,
+/// axes, ...) and `count` data points. Returns an empty list if the first
+/// range header does not describe a consistent block, so the caller can fall
+/// back to heuristics.
+fn read_bruker_raw4_ranges(buf: &[u8]) -> Vec<BrukerRange> {
+    const RECORDS_OFFSET: usize = 61;
+    const RANGE_HEADER_LEN: usize = 0xa0;
+
+    let mut ranges = Vec::new();
+    if !buf.starts_with(b"RAW4") {
+        return ranges;
+    }
+    let Some(records_len) = read_u32_le(buf, 56) else {
+        return ranges;
+    };
+
+    let mut off = RECORDS_OFFSET + records_len as usize;
+    while off + RANGE_HEADER_LEN <= buf.len() {
+        let (Some(start), Some(step), Some(count), Some(datum_size), Some(supp_len)) = (
+            read_f64_le(buf, off + 0x48),
+            read_f64_le(buf, off + 0x50),
+            read_u32_le(buf, off + 0x58),
+            read_u32_le(buf, off + 0x88),
+            read_u32_le(buf, off + 0x8c),
+        ) else {
+            break;
+        };
+        let (count, datum_size) = (count as usize, datum_size as usize);
+        let supp_start = off + RANGE_HEADER_LEN;
+        let data_offset = supp_start.saturating_add(supp_len as usize);
+        if !matches!(datum_size, 4 | 8) {
+            break;
+        }
+        let Some(data_end) = bruker_range_end(buf, start, step, count, data_offset, datum_size)
+        else {
+            break;
+        };
 
-    let names: Vec<String> = (0..archive.len())
-        .filter_map(|i| archive.by_index(i).ok().map(|f| f.name().to_string()))
-        .collect();
+        let mut range = BrukerRange {
+            start,
+            step,
+            count,
+            step_time: read_f32_le(buf, off + 0x5c)
+                .map(f64::from)
+                .filter(|v| *v > 0.0),
+            scan_type: read_c_string(buf, off + 0x20, 0x28).filter(|s| !s.is_empty()),
+            voltage: read_f32_le(buf, off + 0x64)
+                .map(f64::from)
+                .filter(|v| *v > 0.0),
+            current: read_f32_le(buf, off + 0x68)
+                .map(f64::from)
+                .filter(|v| *v > 0.0),
+            wavelength: read_f64_le(buf, off + 0x70).filter(|v| *v > 0.0),
+            start_offset: off + 0x48,
+            step_offset: off + 0x50,
+            data_offset,
+            datum_size,
+            ..BrukerRange::default()
+        };
+        read_bruker_raw4_supplementary(buf, supp_start, data_offset, &mut range);
+        ranges.push(range);
+        off = data_end;
+    }
 
-    // Prioritize Data0/Profile0.txt, or find any Profile*.txt
-    let profile_name = names
-        .iter()
-        .find(|n| n.as_str() == "Data0/Profile0.txt")
-        .or_else(|| {
-            names
-                .iter()
-                .find(|n| n.contains("Profile") && n.ends_with(".txt"))
-        })
-        .ok_or_else(|| Error::FileNotFoundInArchive("Profile*.txt".to_string()))?;
+    ranges
+}
 
-    let file = archive.by_name(profile_name)?;
-    let reader = BufReader::new(file);
+/// Returns the end offset of a range's data block, or `None` if the range
+/// header is inconsistent or its data does not fit in `buf`.
+fn bruker_range_end(
+    buf: &[u8],
+    start: f64,
+    step: f64,
+    count: usize,
+    data_offset: usize,
+    datum_size: usize,
+) -> Option<usize> {
+    if count == 0 || !start.is_finite() || !step.is_finite() || step <= 0.0 {
+        return None;
+    }
+    count
+        .checked_mul(datum_size)
+        .and_then(|len| data_offset.checked_add(len))
+        .filter(|end| *end <= buf.len())
+}
 
-    let mut x = Vec::new();
-    let mut y = Vec::new();
+/// Reads a version 3 (`RAW1.01`) file.
+///
+/// A 712-byte file header is followed by `range count` ranges, each a 304-byte
+/// header, a supplementary block and `f32` intensities.
+fn read_bruker_raw3(buf: &[u8]) -> (Vec<BrukerRange>, Metadata) {
+    const FILE_HEADER_LEN: usize = 712;
+
+    let string =
+        |offset: usize, len: usize| read_c_string(buf, offset, len).filter(|s| !s.is_empty());
+    let positive = |offset: usize| read_f64_le(buf, offset).filter(|v| *v > 0.0);
+    let mut metadata = Metadata {
+        operator: string(36, 72),
+        sample_name: string(326, 60),
+        title: string(386, 160),
+        anode: string(608, 4),
+        goniometer_radius: read_f32_le(buf, 564).map(f64::from).filter(|v| *v > 0.0),
+        start_time: bruker_timestamp(
+            &string(16, 10).unwrap_or_default(),
+            &string(26, 10).unwrap_or_default(),
+        ),
+        ..Metadata::default()
+    };
+    if let Some(site) = string(108, 218) {
+        metadata.extra.insert("SITE".to_string(), site);
+    }
+    let k_alpha2 = positive(632);
+    let radiation = Radiation {
+        k_alpha1: positive(624),
+        k_alpha2,
+        k_beta: positive(640),
+        ratio_k_alpha2_k_alpha1: k_alpha2.and(positive(648)),
+        unit: Some("Angstrom".to_string()),
+        ..Radiation::default()
+    };
+    if radiation.k_alpha1.is_some() {
+        metadata.wavelength = radiation.k_alpha1;
+        metadata.radiation = Some(radiation);
+    }
 
-    for line in reader.lines() {
-        let line = line?;
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
+    let mut ranges = Vec::new();
+    let range_count = read_u32_le(buf, 12).unwrap_or(0);
+    let mut off = FILE_HEADER_LEN;
+    for _ in 0..range_count {
+        let (
+            Some(header_len),
+            Some(steps),
+            Some(start),
+            Some(step),
+            Some(datum_size),
+            Some(extra_len),
+        ) = (
+            read_u32_le(buf, off),
+            read_u32_le(buf, off + 4),
+            read_f64_le(buf, off + 16),
+            read_f64_le(buf, off + 176),
+            read_u32_le(buf, off + 252),
+            read_u32_le(buf, off + 256),
+        )
+        else {
+            break;
+        };
+        if header_len < 260 || datum_size != 4 {
+            break;
         }
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            if let (Ok(val_x), Ok(val_y)) = (parts[0].parse::<f64>(), parts[1].parse::<f64>()) {
-                x.push(val_x);
-                y.push(val_y);
-            }
+        let data_offset = off + header_len as usize + extra_len as usize;
+        let count = steps as usize;
+        let Some(data_end) = bruker_range_end(buf, start, step, count, data_offset, 4) else {
+            break;
+        };
+        let mut axes = Vec::new();
+        if let Some(theta) = read_f64_le(buf, off + 8) {
+            axes.push(AxisSetting {
+                name: "Theta".to_string(),
+                position: Some(theta.to_string()),
+                ..AxisSetting::default()
+            });
         }
+        ranges.push(BrukerRange {
+            start,
+            step,
+            count,
+            step_time: read_f32_le(buf, off + 192)
+                .map(f64::from)
+                .filter(|v| *v > 0.0),
+            voltage: read_u32_le(buf, off + 224)
+                .filter(|v| *v > 0)
+                .map(f64::from),
+            current: read_u32_le(buf, off + 228)
+                .filter(|v| *v > 0)
+                .map(f64::from),
+            wavelength: read_f64_le(buf, off + 240).filter(|v| *v > 0.0),
+            axes,
+            start_offset: off + 16,
+            step_offset: off + 176,
+            data_offset,
+            datum_size: 4,
+            ..BrukerRange::default()
+        });
+        off = data_end;
     }
-    Ok(ParsedPattern { x, y, e: None })
+
+    (ranges, metadata)
 }
 
-/// Parses Panalytical XRDML files (XML-based).
+/// Reads a version 2 (`RAW2`) file.
 ///
-/// Extracts the 2Theta start/end positions and the intensities list.
-pub fn parse_xrdml<R: Read>(reader: R) -> Result<ParsedPattern, Error> {
-    let reader = BufReader::new(reader);
-    let mut xml = Reader::from_reader(reader);
-    xml.config_mut().trim_text(true);
+/// A 256-byte file header is followed by `range count` ranges, each a header
+/// of its own declared length and `f32` intensities.
+fn read_bruker_raw2(buf: &[u8]) -> (Vec<BrukerRange>, Metadata) {
+    const FILE_HEADER_LEN: usize = 256;
+
+    let positive = |offset: usize| read_f32_le(buf, offset).map(f64::from).filter(|v| *v > 0.0);
+    let mut metadata = Metadata {
+        anode: read_c_string(buf, 188, 2).filter(|s| !s.is_empty()),
+        start_time: read_c_string(buf, 168, 20).filter(|s| !s.is_empty()),
+        ..Metadata::default()
+    };
+    let k_alpha2 = positive(194);
+    let radiation = Radiation {
+        k_alpha1: positive(190),
+        k_alpha2,
+        ratio_k_alpha2_k_alpha1: k_alpha2.and(positive(198)),
+        unit: Some("Angstrom".to_string()),
+        ..Radiation::default()
+    };
+    if radiation.k_alpha1.is_some() {
+        metadata.wavelength = radiation.k_alpha1;
+        metadata.radiation = Some(radiation);
+    }
 
-    let mut buf = Vec::new();
-    let mut intensities = Vec::new();
-    let mut in_intensities = false;
-    let mut in_positions_2theta = false;
-    let mut capture_start = false;
-    let mut capture_end = false;
-    let mut start_pos: Option<f64> = None;
-    let mut end_pos: Option<f64> = None;
+    let mut ranges = Vec::new();
+    let range_count = read_u16_le(buf, 4).unwrap_or(0);
+    let mut off = FILE_HEADER_LEN;
+    for _ in 0..range_count {
+        let (Some(header_len), Some(steps), Some(step), Some(start)) = (
+            read_u16_le(buf, off),
+            read_u16_le(buf, off + 2),
+            read_f32_le(buf, off + 12),
+            read_f32_le(buf, off + 16),
+        ) else {
+            break;
+        };
+        if header_len <= 48 {
+            break;
+        }
+        let data_offset = off + header_len as usize;
+        let count = steps as usize;
+        let (start, step) = (f64::from(start), f64::from(step));
+        let Some(data_end) = bruker_range_end(buf, start, step, count, data_offset, 4) else {
+            break;
+        };
+        ranges.push(BrukerRange {
+            start,
+            step,
+            count,
+            step_time: positive(off + 8),
+            start_offset: off + 16,
+            step_offset: off + 12,
+            data_offset,
+            datum_size: 4,
+            ..BrukerRange::default()
+        });
+        off = data_end;
+    }
+
+    (ranges, metadata)
+}
 
+/// Reads a version 1 (`RAW `) file.
+///
+/// Each range is a 152-byte header followed by `f32` intensities; a non-zero
+/// "following range" flag in the header announces another range. Early
+/// DIFFRAC-AT files do not repeat the `RAW ` magic before further ranges.
+fn read_bruker_raw1(buf: &[u8]) -> (Vec<BrukerRange>, Metadata) {
+    const RANGE_HEADER_LEN: usize = 152;
+    const NOT_SET: f32 = -1.0e6;
+
+    let mut metadata = Metadata::default();
+    let mut ranges = Vec::new();
+    let mut off = 4;
     loop {
-        match xml.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) => match e.local_name().as_ref() {
-                b"positions" => {
-                    in_positions_2theta = false;
-                    for attr in e.attributes() {
-                        let attr = attr.map_err(|err| {
-                            Error::Parse(format!("XRDML attribute error: {err}"))
-                        })?;
-                        if attr.key.as_ref() == b"axis" {
-                            let axis = attr
-                                .unescape_value()
-                                .map_err(|err| {
-                                    Error::Parse(format!(
-                                        "XRDML attribute decode error: {err}"
-                                    ))
-                                })?
-                                .into_owned();
-                            if axis == "2Theta" {
-                                in_positions_2theta = true;
-                            }
-                        }
-                    }
-                }
-                b"startPosition" => {
-                    if in_positions_2theta {
-                        capture_start = true;
-                    }
-                }
-                b"endPosition" => {
-                    if in_positions_2theta {
-                        capture_end = true;
-                    }
-                }
-                b"intensities" => {
-                    in_intensities = true;
-                }
-                _ => {}
-            },
-            Ok(Event::Text(e)) => {
-                let text = e
-                    .decode()
-                    .map_err(|err| Error::Parse(format!("XRDML text decode error: {err}")))?;
-                let text = text.trim();
-                if text.is_empty() {
-                    // Skip empty text nodes.
-                } else if capture_start {
-                    start_pos = Some(text.parse::<f64>().map_err(|_| {
-                        Error::Parse("XRDML invalid 2Theta start position".into())
-                    })?);
-                } else if capture_end {
-                    end_pos = Some(text.parse::<f64>().map_err(|_| {
-                        Error::Parse("XRDML invalid 2Theta end position".into())
-                    })?);
-                } else if in_intensities {
-                    for part in text.split_whitespace() {
-                        if let Ok(value) = part.parse::<f64>() {
-                            intensities.push(value);
-                        }
-                    }
-                }
+        if !ranges.is_empty() && buf.get(off..off + 4) == Some(b"RAW ".as_slice()) {
+            off += 4;
+        }
+        let (Some(steps), Some(step), Some(start), Some(following)) = (
+            read_u32_le(buf, off),
+            read_f32_le(buf, off + 8),
+            read_f32_le(buf, off + 20),
+            read_u32_le(buf, off + 148),
+        ) else {
+            break;
+        };
+        let data_offset = off + RANGE_HEADER_LEN;
+        let count = steps as usize;
+        let (start, step) = (f64::from(start), f64::from(step));
+        let Some(data_end) = bruker_range_end(buf, start, step, count, data_offset, 4) else {
+            break;
+        };
+
+        let mut axes = Vec::new();
+        for (name, offset) in [("Theta", 24), ("Chi", 28), ("Phi", 32)] {
+            if let Some(position) = read_f32_le(buf, off + offset).filter(|v| *v != NOT_SET) {
+                axes.push(AxisSetting {
+                    name: name.to_string(),
+                    position: Some(position.to_string()),
+                    ..AxisSetting::default()
+                });
             }
-            Ok(Event::End(e)) => match e.local_name().as_ref() {
-                b"positions" => {
-                    in_positions_2theta = false;
-                }
-                b"startPosition" => {
-                    capture_start = false;
-                }
-                b"endPosition" => {
-                    capture_end = false;
-                }
-                b"intensities" => {
-                    in_intensities = false;
-                    if !intensities.is_empty() && start_pos.is_some() && end_pos.is_some() {
-                        break;
-                    }
+        }
+        if ranges.is_empty() {
+            let positive = |offset: usize| {
+                read_f32_le(buf, off + offset)
+                    .map(f64::from)
+                    .filter(|v| *v > 0.0)
+            };
+            metadata.sample_name = read_c_string(buf, off + 36, 32).filter(|s| !s.is_empty());
+            let radiation = Radiation {
+                k_alpha1: positive(68),
+                k_alpha2: positive(72),
+                unit: Some("Angstrom".to_string()),
+                ..Radiation::default()
+            };
+            if radiation.k_alpha1.is_some() {
+                metadata.wavelength = radiation.k_alpha1;
+                metadata.radiation = Some(radiation);
+            }
+        }
+        ranges.push(BrukerRange {
+            start,
+            step,
+            count,
+            step_time: read_f32_le(buf, off + 4)
+                .map(f64::from)
+                .filter(|v| *v > 0.0),
+            axes,
+            start_offset: off + 20,
+            step_offset: off + 8,
+            data_offset,
+            datum_size: 4,
+            ..BrukerRange::default()
+        });
+
+        if following == 0 {
+            break;
+        }
+        off = data_end;
+    }
+
+    (ranges, metadata)
+}
+
+/// Reads the typed supplementary records of a RAW4 range.
+///
+/// Type 40 records name the detector; type 50 records give the start
+/// position of a goniometer axis or slit.
+fn read_bruker_raw4_supplementary(buf: &[u8], start: usize, end: usize, range: &mut BrukerRange) {
+    let mut off = start;
+    while off + 8 <= end {
+        let (Some(kind), Some(len)) = (read_u32_le(buf, off), read_u32_le(buf, off + 4)) else {
+            break;
+        };
+        let len = len as usize;
+        if len < 8 || off + len > end {
+            break;
+        }
+        match kind {
+            40 if len >= 36 => {
+                range.detector = read_c_string(buf, off + 12, 24).filter(|s| !s.is_empty());
+            }
+            50 if len >= 64 => {
+                let name = read_c_string(buf, off + 12, 24).unwrap_or_default();
+                // Some writers list an axis more than once; keep the first.
+                if !name.is_empty() && !range.axes.iter().any(|axis| axis.name == name) {
+                    range.axes.push(AxisSetting {
+                        name,
+                        position: read_f64_le(buf, off + 56).map(|v| v.to_string()),
+                        ..AxisSetting::default()
+                    });
                 }
-                _ => {}
-            },
-            Ok(Event::Eof) => break,
-            Err(err) => {
-                return Err(Error::Parse(format!("XRDML parse error: {err}")));
             }
             _ => {}
         }
-        buf.clear();
+        off += len;
     }
+}
 
-    let start = start_pos
-        .ok_or_else(|| Error::Parse("XRDML missing 2Theta start position".into()))?;
-    let end =
-        end_pos.ok_or_else(|| Error::Parse("XRDML missing 2Theta end position".into()))?;
-
-    if intensities.is_empty() {
-        return Err(Error::Parse("XRDML intensities not found".into()));
+/// Decodes the intensities of the `index`-th range into a pattern.
+fn read_bruker_range(
+    buf: &[u8],
+    index: usize,
+    range: &BrukerRange,
+    file_metadata: &Metadata,
+) -> Result<ParsedPattern, Error> {
+    let mut y = Vec::with_capacity(range.count);
+    for i in 0..range.count {
+        let val = read_f32_le(buf, range.data_offset + i * range.datum_size)
+            .ok_or_else(|| Error::parse("Bruker RAW intensity data truncated"))?;
+        y.push(val as f64);
     }
+    let x = (0..range.count)
+        .map(|i| range.start + range.step * (i as f64))
+        .collect();
 
-    let mut x = Vec::with_capacity(intensities.len());
-    if intensities.len() == 1 {
-        x.push(start);
-    } else {
-        let step = (end - start) / (intensities.len() as f64 - 1.0);
-        for i in 0..intensities.len() {
-            x.push(start + (i as f64) * step);
-        }
+    let mut metadata = file_metadata.clone();
+    let axis = match range.scan_type.as_deref() {
+        Some("Locked Coupled" | "Unlocked Coupled" | "Detector Scan") => Some("2Theta"),
+        _ => None,
+    };
+    metadata.scan = Some(Scan {
+        index: Some(index as u32),
+        axis: axis.map(str::to_string),
+        mode: range.scan_type.clone(),
+        start: Some(range.start),
+        end: Some(range.start + range.step * (range.count.saturating_sub(1) as f64)),
+        step: Some(range.step),
+        ..Scan::default()
+    });
+    metadata.counting_time = range.step_time;
+    if metadata.wavelength.is_none() {
+        metadata.wavelength = range.wavelength;
+    }
+    let generator = XrayGenerator {
+        target: metadata.anode.clone(),
+        voltage: range.voltage,
+        current: range.current,
+        focus: None,
+    };
+    let conditions = MeasurementConditions {
+        generator: Some(generator).filter(|g| *g != XrayGenerator::default()),
+        detector: range.detector.clone(),
+        axes: range.axes.clone(),
+        ..MeasurementConditions::default()
+    };
+    if !conditions.is_empty() {
+        metadata.conditions = Some(conditions);
     }
 
     Ok(ParsedPattern {
         x,
-        y: intensities,
+        y,
         e: None,
+        attenuation: None,
+        metadata,
+        warnings: Vec::new(),
     })
 }
 
-/// Parses GSAS RAW files.
+/// Locates the intensity block and axis metadata by scanning the file.
 ///
-/// Expects a `BANK` header line to determine start angle and step size.
-pub fn parse_gsas_raw<R: Read>(reader: R) -> Result<ParsedPattern, Error> {
-    let reader = BufReader::new(reader);
-    let mut lines = reader.lines();
+/// Only used for RAW variants the structured parsers do not recognize.
+fn parse_bruker_raw_heuristic(buf: &[u8], metadata: Metadata) -> Result<ParsedPattern, Error> {
+    let selected = diagnose_bruker_heuristic(buf)
+        .selected
+        .ok_or_else(|| Error::parse("Failed to locate Bruker RAW start/step metadata"))?;
+    let (start, step) = (
+        selected.start.unwrap_or_default(),
+        selected.step.unwrap_or_default(),
+    );
+    let count = selected.count;
+
+    let mut y = Vec::with_capacity(count);
+    for i in 0..count {
+        let off = selected.data_offset + i * selected.stride + selected.value_offset;
+        let val = read_f32_le(buf, off)
+            .ok_or_else(|| Error::parse("Bruker RAW intensity data truncated"))?;
+        y.push(val as f64);
+    }
 
-    let mut start = 0.0;
-    let mut step = 0.0;
+    let mut x = Vec::with_capacity(count);
+    for i in 0..count {
+        x.push(start + step * (i as f64));
+    }
 
-    let mut header_found = false;
+    Ok(ParsedPattern {
+        x,
+        y,
+        e: None,
+        attenuation: None,
+        metadata,
+        warnings: Vec::new(),
+    })
+}
 
-    for line_res in lines.by_ref() {
-        let line = line_res?;
-        if line.starts_with("BANK") {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            // BANK 1 4941 494 CONST 1600.0 1.7 0.0 0.0 STD
-            if parts.len() >= 7 {
-                let start_raw = parts[5]
-                    .parse::<f64>()
-                    .map_err(|_| Error::Parse("Invalid start".into()))?;
-                let step_raw = parts[6]
-                    .parse::<f64>()
-                    .map_err(|_| Error::Parse("Invalid step".into()))?;
-
-                // GSAS standard: centidegrees
-                start = start_raw / 100.0;
-                step = step_raw / 100.0;
-                header_found = true;
-                break;
-            }
-        }
-    }
+/// Reports how the intensity block of a Bruker RAW file is located.
+///
+/// Files the structured parsers recognize report their first range header;
+/// other files report every candidate of the heuristic search.
+pub fn parse_bruker_raw_diagnostics<R: Read>(mut reader: R) -> Result<BrukerRawDiagnostics, Error> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
 
-    if !header_found {
-        return Err(Error::Parse(
-            "BANK header not found in RAW file".into(),
-        ));
+    if !buf.starts_with(b"RAW") {
+        return Err(Error::parse("Unsupported Bruker RAW header"));
     }
 
-    let mut y = Vec::new();
+    let (version, ranges, _) = read_bruker_raw_structure(&buf);
+    let version = Some(version.to_string()).filter(|v| !v.is_empty());
+    let Some(range) = ranges.first() else {
+        return Ok(BrukerRawDiagnostics {
+            version,
+            ..diagnose_bruker_heuristic(&buf)
+        });
+    };
+    Ok(BrukerRawDiagnostics {
+        version,
+        heuristic: false,
+        selected: Some(BrukerCandidate {
+            layout: "structured".to_string(),
+            count: range.count,
+            stride: range.datum_size,
+            data_offset: range.data_offset,
+            value_offset: 0,
+            start_offset: Some(range.start_offset),
+            step_offset: Some(range.step_offset),
+            start: Some(range.start),
+            step: Some(range.step),
+            score: None,
+            reason: None,
+        }),
+        rejected: Vec::new(),
+        confidence: 1.0,
+    })
+}
 
-    for line in lines {
-        let line = line?;
-        if line.starts_with("BANK") {
-            break;
+/// Ranks the heuristic candidates for the intensity block and axis pair.
+///
+/// On equal scores the candidate found first wins: interleaved blocks before
+/// plain `f32` blocks, and earlier axis pairs before later ones.
+fn diagnose_bruker_heuristic(buf: &[u8]) -> BrukerRawDiagnostics {
+    let mut scored: Vec<BrukerCandidate> = Vec::new();
+    let mut rejected = Vec::new();
+    for (name, layout) in [
+        ("interleaved", find_bruker_interleaved_tail_block(buf)),
+        ("plain_f32", find_bruker_plain_f32_tail_block(buf)),
+    ] {
+        let Some(layout) = layout else {
+            continue;
+        };
+        let candidate = BrukerCandidate {
+            layout: name.to_string(),
+            count: layout.count as usize,
+            stride: layout.stride,
+            data_offset: layout.data_offset,
+            value_offset: layout.value_offset,
+            ..BrukerCandidate::default()
+        };
+        if !bruker_layout_data_plausible(buf, layout) {
+            rejected.push(BrukerCandidate {
+                reason: Some("implausible intensity values".to_string()),
+                ..candidate
+            });
+            continue;
         }
-        let parts = line.split_whitespace();
-        for part in parts {
-            if let Ok(val) = part.parse::<f64>() {
-                y.push(val);
-            }
+        let count_offsets = find_bruker_count_offsets(buf, layout.count, layout.data_offset);
+        let pairs = find_bruker_start_step(buf, &count_offsets, layout.count, layout.data_offset);
+        if pairs.is_empty() {
+            rejected.push(BrukerCandidate {
+                reason: Some("no plausible start/step pair".to_string()),
+                ..candidate
+            });
+            continue;
+        }
+        for (offset, start, step, score) in pairs {
+            scored.push(BrukerCandidate {
+                start_offset: Some(offset),
+                step_offset: Some(offset + 8),
+                start: Some(start),
+                step: Some(step),
+                score: Some(score),
+                ..candidate.clone()
+            });
         }
     }
 
-    // Generate x
-    let mut x = Vec::with_capacity(y.len());
-    for i in 0..y.len() {
-        x.push(start + (i as f64) * step);
+    // A stable sort keeps the first-found candidate ahead on equal scores.
+    scored.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let mut scored = scored.into_iter();
+    let selected = scored.next();
+    let best = selected.as_ref().and_then(|c| c.score).unwrap_or(0.0);
+    // Candidates that agree with the selected axis are not competitors.
+    let runner_up = scored
+        .clone()
+        .filter(|c| {
+            selected
+                .as_ref()
+                .is_some_and(|s| (c.start, c.step) != (s.start, s.step))
+        })
+        .find_map(|c| c.score);
+    let confidence = match (&selected, runner_up) {
+        (None, _) => 0.0,
+        (Some(_), Some(runner_up)) if best > 0.0 => (1.0 - runner_up / best).clamp(0.0, 1.0),
+        (Some(_), _) => 1.0,
+    };
+
+    let mut others: Vec<BrukerCandidate> = scored
+        .map(|c| BrukerCandidate {
+            reason: Some("lower score".to_string()),
+            ..c
+        })
+        .collect();
+    others.extend(rejected);
+    BrukerRawDiagnostics {
+        version: None,
+        heuristic: true,
+        selected,
+        rejected: others,
+        confidence,
     }
-
-    Ok(ParsedPattern { x, y, e: None })
 }
 
-/// Parses Bruker binary RAW files.
+/// Reads the RAW4 file header and its file-level meta-records.
 ///
-/// Uses heuristics to locate the intensity block and axis metadata.
-///
-/// Bruker RAW4 files are not fully documented and may store intensity points
-/// either as contiguous `f32` values or as interleaved records near the file
-/// tail (e.g. `f32 value` + `u32 status`).
-pub fn parse_bruker_raw<R: Read>(mut reader: R) -> Result<ParsedPattern, Error> {
-    let mut buf = Vec::new();
-    reader.read_to_end(&mut buf)?;
+/// The header holds the measurement date and time, followed at offset 61 by a
+/// run of records (length at offset 56). Type 10 records carry a tag/value
+/// string pair such as `USER` or `SAMPLEID`; the type 30 record describes the
+/// tube, including anode and wavelengths.
+fn read_bruker_raw4_metadata(buf: &[u8]) -> Metadata {
+    const RECORDS_OFFSET: usize = 61;
+
+    let mut metadata = Metadata::default();
+    if !buf.starts_with(b"RAW4") {
+        return metadata;
+    }
 
-    if !buf.starts_with(b"RAW") {
-        return Err(Error::Parse(
-            "Unsupported Bruker RAW header".into(),
-        ));
-    }
-
-    let mut selected: Option<(BrukerDataLayout, f64, f64, f64)> = None;
-    for layout in [
-        find_bruker_interleaved_tail_block(&buf),
-        find_bruker_plain_f32_tail_block(&buf),
-    ]
-    .into_iter()
-    .flatten()
-    {
-        if !bruker_layout_data_plausible(&buf, layout) {
-            continue;
+    let date = read_c_string(buf, 12, 12).unwrap_or_default();
+    let time = read_c_string(buf, 24, 12).unwrap_or_default();
+    metadata.start_time = bruker_timestamp(&date, &time);
+
+    let records_len = read_u32_le(buf, 56).unwrap_or(0) as usize;
+    let records_end = (RECORDS_OFFSET + records_len).min(buf.len());
+    let mut off = RECORDS_OFFSET;
+    while off + 8 <= records_end {
+        let (Some(kind), Some(len)) = (read_u32_le(buf, off), read_u32_le(buf, off + 4)) else {
+            break;
+        };
+        let len = len as usize;
+        if len < 8 || off + len > records_end {
+            break;
         }
-        let count_offsets = find_bruker_count_offsets(&buf, layout.count, layout.data_offset);
-        if let Some((start, step)) = find_bruker_start_step(
-            &buf,
-            &count_offsets,
-            layout.count,
-            layout.data_offset,
-        ) {
-            let score = score_bruker_start_step(start, step, layout.count);
-            match selected {
-                Some((_, _, _, best_score)) if score <= best_score => {}
-                _ => selected = Some((layout, start, step, score)),
+        match kind {
+            10 if len >= 36 => {
+                let tag = read_c_string(buf, off + 12, 24).unwrap_or_default();
+                let value = read_c_string(buf, off + 36, len - 36).unwrap_or_default();
+                if !tag.is_empty() && !value.is_empty() {
+                    match tag.as_str() {
+                        "USER" => metadata.operator = Some(value),
+                        "SAMPLEID" => metadata.sample_name = Some(value),
+                        "COMMENT" => metadata.title = Some(value),
+                        _ => {
+                            metadata.extra.insert(tag, value);
+                        }
+                    }
+                }
+            }
+            30 if len >= 0x78 => {
+                let positive = |offset: usize| read_f64_le(buf, off + offset).filter(|v| *v > 0.0);
+                let k_alpha2 = positive(0x58);
+                let radiation = Radiation {
+                    k_alpha1: positive(0x50),
+                    k_alpha2,
+                    k_beta: positive(0x60),
+                    ratio_k_alpha2_k_alpha1: k_alpha2.and(positive(0x68)),
+                    unit: Some("Angstrom".to_string()),
+                    ..Radiation::default()
+                };
+                if radiation.k_alpha1.is_some() {
+                    metadata.radiation = Some(radiation);
+                }
+                metadata.wavelength = read_f64_le(buf, off + 0x50).filter(|v| *v > 0.0);
+                metadata.anode = read_c_string(buf, off + 0x74, 4).filter(|s| !s.is_empty());
             }
+            _ => {}
         }
+        off += len;
     }
 
-    let (layout, start, step, _) = selected.ok_or_else(|| {
-        Error::Parse("Failed to locate Bruker RAW start/step metadata".into())
-    })?;
-    let count = layout.count;
-
-    let count_usize = count as usize;
-    let mut y = Vec::with_capacity(count_usize);
-    for i in 0..count_usize {
-        let off = layout.data_offset + i * layout.stride + layout.value_offset;
-        let val = read_f32_le(&buf, off).ok_or_else(|| {
-            Error::Parse("Bruker RAW intensity data truncated".into())
-        })?;
-        y.push(val as f64);
-    }
+    metadata
+}
 
-    let mut x = Vec::with_capacity(count_usize);
-    for i in 0..count_usize {
-        x.push(start + step * (i as f64));
-    }
+/// Formats a Bruker `MM/DD/YYYY` date and a time as an ISO 8601 timestamp.
+///
+/// Dates in any other form are kept as written.
+fn bruker_timestamp(date: &str, time: &str) -> Option<String> {
+    let parts: Vec<&str> = date.split('/').collect();
+    let joined = match parts.as_slice() {
+        [month, day, year] if year.len() == 4 => {
+            let time = if time.is_empty() {
+                String::new()
+            } else {
+                format!("T{time}")
+            };
+            format!("{year}-{month:0>2}-{day:0>2}{time}")
+        }
+        _ => format!("{date} {time}").trim().to_string(),
+    };
+    Some(joined).filter(|s| !s.is_empty())
+}
 
-    Ok(ParsedPattern { x, y, e: None })
+/// Reads a NUL-terminated (or field-length) ASCII string, trimming whitespace.
+fn read_c_string(buf: &[u8], offset: usize, len: usize) -> Option<String> {
+    let bytes = buf.get(offset..offset + len)?;
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    Some(String::from_utf8_lossy(&bytes[..end]).trim().to_string())
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -406,7 +2490,7 @@ fn find_bruker_plain_f32_tail_block(buf: &[u8]) -> Option<BrukerDataLayout> {
 
     for off in 0..len.saturating_sub(4) {
         let count = read_u32_le(buf, off)?;
-        if count < 10 || count > 5_000_000 {
+        if !(10..=5_000_000).contains(&count) {
             continue;
         }
         let data_len = (count as usize) * 4;
@@ -532,52 +2616,43 @@ fn find_bruker_count_offsets(buf: &[u8], count: u32, search_end: usize) -> Vec<u
     offsets
 }
 
+/// Lists every plausible `(offset, start, step, score)` axis pair, in file
+/// order.
+///
+/// Pairs just before a count marker are preferred; the whole pre-data region
+/// is only scanned when no marker yields a plausible pair.
 fn find_bruker_start_step(
     buf: &[u8],
     count_offsets: &[usize],
     count: u32,
     search_end: usize,
-) -> Option<(f64, f64)> {
-    let mut best: Option<(f64, f64, f64)> = None;
-
-    for &count_offset in count_offsets {
-        if let Some(start_off) = count_offset.checked_sub(16) {
-            if let (Some(start), Some(step)) =
-                (read_f64_le(buf, start_off), read_f64_le(buf, start_off + 8))
-            {
-                if bruker_start_step_valid(start, step, count) {
-                    let score = score_bruker_start_step(start, step, count);
-                    match best {
-                        Some((_, _, best_score)) if score <= best_score => {}
-                        _ => best = Some((start, step, score)),
-                    }
-                }
-            }
-        }
-    }
+) -> Vec<(usize, f64, f64, f64)> {
+    let pair_at = |start_off: usize| {
+        let start = read_f64_le(buf, start_off)?;
+        let step = read_f64_le(buf, start_off + 8)?;
+        bruker_start_step_valid(start, step, count).then(|| {
+            (
+                start_off,
+                start,
+                step,
+                score_bruker_start_step(start, step, count),
+            )
+        })
+    };
 
-    if best.is_some() {
-        return best.map(|(start, step, _)| (start, step));
+    let pairs: Vec<_> = count_offsets
+        .iter()
+        .filter_map(|count_offset| count_offset.checked_sub(16))
+        .filter_map(pair_at)
+        .collect();
+    if !pairs.is_empty() {
+        return pairs;
     }
 
     // Some Bruker RAW variants do not expose a count marker adjacent to axis
     // metadata. Fall back to scanning the pre-data region for plausible pairs.
     let end = search_end.min(buf.len().saturating_sub(16));
-    for off in 0..=end {
-        let (start, step) = match (read_f64_le(buf, off), read_f64_le(buf, off + 8)) {
-            (Some(start), Some(step)) => (start, step),
-            _ => continue,
-        };
-        if bruker_start_step_valid(start, step, count) {
-            let score = score_bruker_start_step(start, step, count);
-            match best {
-                Some((_, _, best_score)) if score <= best_score => {}
-                _ => best = Some((start, step, score)),
-            }
-        }
-    }
-
-    best.map(|(start, step, _)| (start, step))
+    (0..=end).filter_map(pair_at).collect()
 }
 
 fn bruker_start_step_valid(start: f64, step: f64, count: u32) -> bool {
@@ -608,6 +2683,11 @@ fn score_bruker_start_step(start: f64, step: f64, count: u32) -> f64 {
     score
 }
 
+fn read_u16_le(buf: &[u8], offset: usize) -> Option<u16> {
+    let bytes = buf.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes(bytes.try_into().ok()?))
+}
+
 fn read_u32_le(buf: &[u8], offset: usize) -> Option<u32> {
     let bytes = buf.get(offset..offset + 4)?;
     Some(u32::from_le_bytes(bytes.try_into().ok()?))
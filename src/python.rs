@@ -1,7 +1,14 @@
-use crate::{read, read_reader, Pattern, Error};
-use pyo3::exceptions::{PyIOError, PyValueError};
+use crate::{
+    diagnose_bruker_raw, diagnose_bruker_raw_bytes, read_all_reader_with, read_all_with,
+    read_reader_with, read_report, read_report_reader, read_with, read_with_format, read_xrdml_map,
+    read_xrdml_map_bytes, AxisSetting, BankHeader, BrukerCandidate, BrukerRawDiagnostics, Error,
+    Format, Grid, Location, MeasurementConditions, Metadata, Pattern, Radiation, ReadOptions,
+    ReadReport, ReciprocalSpaceMap, Scan, Warning, WarningKind, XrayGenerator,
+};
+use pyo3::exceptions::{PyIOError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
+use pyo3::types::{PyBytes, PyDict};
+use std::fs::File;
 use std::io::Cursor;
 
 /// Convert a Rust library error into a Python exception.
@@ -9,7 +16,14 @@ fn to_py_err(err: Error) -> PyErr {
     match err {
         Error::Io(err) => PyIOError::new_err(err.to_string()),
         Error::Zip(err) => PyValueError::new_err(err.to_string()),
-        Error::Parse(msg) => PyValueError::new_err(msg),
+        Error::Parse {
+            message,
+            location: None,
+        } => PyValueError::new_err(message),
+        Error::Parse {
+            message,
+            location: Some(location),
+        } => PyValueError::new_err(format!("{message} at {location}")),
         Error::UnknownFormat => PyValueError::new_err("Unknown format"),
         Error::FileNotFoundInArchive(name) => {
             PyValueError::new_err(format!("File not found in archive: {}", name))
@@ -17,27 +31,200 @@ fn to_py_err(err: Error) -> PyErr {
     }
 }
 
+#[pymethods]
+impl Pattern {
+    /// Multiply the attenuation factors into `y` (and `e`) in place.
+    #[pyo3(name = "apply_attenuation")]
+    fn apply_attenuation_py(&mut self) {
+        self.apply_attenuation();
+    }
+}
+
+#[pymethods]
+impl ReciprocalSpaceMap {
+    /// Convert every point to `(qx, qz)` using the wavelength in `metadata`.
+    #[pyo3(name = "to_q")]
+    fn to_q_py(&self) -> PyResult<(Grid, Grid)> {
+        self.to_q().map_err(to_py_err)
+    }
+
+    /// Convert every point to `(qx, qz)` for a wavelength in angstrom.
+    #[pyo3(name = "to_q_with_wavelength")]
+    fn to_q_with_wavelength_py(&self, wavelength: f64) -> (Grid, Grid) {
+        self.to_q_with_wavelength(wavelength)
+    }
+}
+
+/// Build `ReadOptions` from the keyword arguments of the `read*` functions.
+fn read_options(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<ReadOptions> {
+    let mut options = ReadOptions::new();
+    let Some(kwargs) = kwargs else {
+        return Ok(options);
+    };
+    for (key, value) in kwargs.iter() {
+        let key: String = key.extract()?;
+        options = match key.as_str() {
+            "apply_attenuation" => options.apply_attenuation(value.extract()?),
+            "gsas_centidegrees" => options.gsas_centidegrees(value.extract()?),
+            "rasx_data_set" => options.rasx_data_set(value.extract()?),
+            "xrdml_axis" => options.xrdml_axis(value.extract::<String>()?),
+            "xrdml_counts_per_second" => options.xrdml_counts_per_second(value.extract()?),
+            "comment_prefixes" => options.comment_prefixes(value.extract::<Vec<String>>()?),
+            "strict" => options.strict(value.extract()?),
+            _ => {
+                return Err(PyTypeError::new_err(format!(
+                    "unexpected keyword argument '{key}'"
+                )))
+            }
+        };
+    }
+    Ok(options)
+}
+
 /// Load a pattern from a file path.
 #[pyfunction(name = "read")]
-fn read_py(path: &str) -> PyResult<Pattern> {
-    read(path).map_err(to_py_err)
+#[pyo3(signature = (path, **options))]
+fn read_py(path: &str, options: Option<&Bound<'_, PyDict>>) -> PyResult<Pattern> {
+    read_with(path, &read_options(options)?).map_err(to_py_err)
 }
 
 /// Load a pattern from raw bytes with a filename hint.
 #[pyfunction]
+#[pyo3(signature = (data, filename, **options))]
 fn read_bytes(
     data: &Bound<'_, PyBytes>,
     filename: &str,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<Pattern> {
     let cursor = Cursor::new(data.as_bytes());
-    read_reader(cursor, filename).map_err(to_py_err)
+    read_reader_with(cursor, filename, &read_options(options)?).map_err(to_py_err)
+}
+
+/// Load a pattern and its parse warnings from a file path.
+#[pyfunction(name = "read_report")]
+#[pyo3(signature = (path, **options))]
+fn read_report_py(path: &str, options: Option<&Bound<'_, PyDict>>) -> PyResult<ReadReport> {
+    read_report(path, &read_options(options)?).map_err(to_py_err)
+}
+
+/// Load a pattern and its parse warnings from raw bytes with a filename hint.
+#[pyfunction]
+#[pyo3(signature = (data, filename, **options))]
+fn read_bytes_report(
+    data: &Bound<'_, PyBytes>,
+    filename: &str,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<ReadReport> {
+    let cursor = Cursor::new(data.as_bytes());
+    read_report_reader(cursor, filename, &read_options(options)?).map_err(to_py_err)
+}
+
+/// Load a pattern from a file path with an explicitly chosen format.
+#[pyfunction(name = "read_with_format")]
+#[pyo3(signature = (path, format, **options))]
+fn read_with_format_py(
+    path: &str,
+    format: Format,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Pattern> {
+    let options = read_options(options)?;
+    let file = File::open(path).map_err(|err| to_py_err(err.into()))?;
+    read_with_format(file, format, &options).map_err(to_py_err)
+}
+
+/// Load a pattern from raw bytes with an explicitly chosen format.
+#[pyfunction]
+#[pyo3(signature = (data, format, **options))]
+fn read_bytes_with_format(
+    data: &Bound<'_, PyBytes>,
+    format: Format,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Pattern> {
+    let cursor = Cursor::new(data.as_bytes());
+    read_with_format(cursor, format, &read_options(options)?).map_err(to_py_err)
+}
+
+/// Load every pattern stored in a file.
+#[pyfunction(name = "read_all")]
+#[pyo3(signature = (path, **options))]
+fn read_all_py(path: &str, options: Option<&Bound<'_, PyDict>>) -> PyResult<Vec<Pattern>> {
+    read_all_with(path, &read_options(options)?).map_err(to_py_err)
+}
+
+/// Load every pattern from raw bytes with a filename hint.
+#[pyfunction]
+#[pyo3(signature = (data, filename, **options))]
+fn read_all_bytes(
+    data: &Bound<'_, PyBytes>,
+    filename: &str,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Vec<Pattern>> {
+    let cursor = Cursor::new(data.as_bytes());
+    read_all_reader_with(cursor, filename, &read_options(options)?).map_err(to_py_err)
+}
+
+/// Load every scan of an XRDML file as a reciprocal space map.
+#[pyfunction(name = "read_xrdml_map")]
+#[pyo3(signature = (path, **options))]
+fn read_xrdml_map_py(
+    path: &str,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<ReciprocalSpaceMap> {
+    read_xrdml_map(path, &read_options(options)?).map_err(to_py_err)
+}
+
+/// Load an XRDML reciprocal space map from raw bytes.
+#[pyfunction(name = "read_xrdml_map_bytes")]
+#[pyo3(signature = (data, **options))]
+fn read_xrdml_map_bytes_py(
+    data: &Bound<'_, PyBytes>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<ReciprocalSpaceMap> {
+    read_xrdml_map_bytes(data.as_bytes(), &read_options(options)?).map_err(to_py_err)
+}
+
+/// Report how the data block of a Bruker RAW file was located.
+#[pyfunction(name = "diagnose_bruker_raw")]
+fn diagnose_bruker_raw_py(path: &str) -> PyResult<BrukerRawDiagnostics> {
+    diagnose_bruker_raw(path).map_err(to_py_err)
+}
+
+/// Report how the data block of Bruker RAW bytes was located.
+#[pyfunction(name = "diagnose_bruker_raw_bytes")]
+fn diagnose_bruker_raw_bytes_py(data: &Bound<'_, PyBytes>) -> PyResult<BrukerRawDiagnostics> {
+    diagnose_bruker_raw_bytes(data.as_bytes()).map_err(to_py_err)
 }
 
 /// Python module definition for the `geddes` extension.
 #[pymodule]
 fn geddes(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Pattern>()?;
+    m.add_class::<Format>()?;
+    m.add_class::<Metadata>()?;
+    m.add_class::<Radiation>()?;
+    m.add_class::<Scan>()?;
+    m.add_class::<MeasurementConditions>()?;
+    m.add_class::<XrayGenerator>()?;
+    m.add_class::<AxisSetting>()?;
+    m.add_class::<BankHeader>()?;
+    m.add_class::<BrukerRawDiagnostics>()?;
+    m.add_class::<BrukerCandidate>()?;
+    m.add_class::<ReadReport>()?;
+    m.add_class::<Warning>()?;
+    m.add_class::<WarningKind>()?;
+    m.add_class::<Location>()?;
+    m.add_class::<ReciprocalSpaceMap>()?;
     m.add_function(wrap_pyfunction!(read_py, m)?)?;
     m.add_function(wrap_pyfunction!(read_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(read_report_py, m)?)?;
+    m.add_function(wrap_pyfunction!(read_bytes_report, m)?)?;
+    m.add_function(wrap_pyfunction!(read_with_format_py, m)?)?;
+    m.add_function(wrap_pyfunction!(read_bytes_with_format, m)?)?;
+    m.add_function(wrap_pyfunction!(read_all_py, m)?)?;
+    m.add_function(wrap_pyfunction!(read_all_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(read_xrdml_map_py, m)?)?;
+    m.add_function(wrap_pyfunction!(read_xrdml_map_bytes_py, m)?)?;
+    m.add_function(wrap_pyfunction!(diagnose_bruker_raw_py, m)?)?;
+    m.add_function(wrap_pyfunction!(diagnose_bruker_raw_bytes_py, m)?)?;
     Ok(())
 }
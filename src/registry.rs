@@ -0,0 +1,612 @@
+use crate::error::Error;
+use crate::format::{detect_format, Format, FormatDetection};
+use crate::options::ReadOptions;
+use crate::report::ReadReport;
+use crate::{file_extension, finish_report, is_binary, parse_format, parse_format_all, Pattern};
+use std::fmt;
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+/// A seekable byte source, as handed to [`PatternReader`] methods.
+///
+/// Implemented for every `Read + Seek` type.
+pub trait ReadSeek: Read + Seek {}
+
+impl<T: Read + Seek + ?Sized> ReadSeek for T {}
+
+/// A parser for one file format.
+///
+/// Implement this trait to load in-house formats through [`read`](crate::read)
+/// and the other `read_*` functions, then add the reader with
+/// [`register_reader`]. The built-in formats are registered the same way.
+///
+/// Every method receives the source rewound to the start.
+///
+/// # Examples
+///
+/// ```
+/// use geddes::{register_reader, Error, Pattern, PatternReader, ReadOptions, ReadSeek};
+///
+/// struct Counts;
+///
+/// impl PatternReader for Counts {
+///     fn name(&self) -> &str {
+///         "Counts"
+///     }
+///
+///     fn extensions(&self) -> &[&str] {
+///         &["cnt"]
+///     }
+///
+///     fn detect(&self, reader: &mut dyn ReadSeek) -> Result<Option<f64>, Error> {
+///         let mut magic = [0u8; 6];
+///         let matched = reader.read_exact(&mut magic).is_ok() && &magic == b"COUNTS";
+///         Ok(matched.then_some(1.0))
+///     }
+///
+///     fn parse_all(
+///         &self,
+///         reader: &mut dyn ReadSeek,
+///         _options: &ReadOptions,
+///     ) -> Result<Vec<Pattern>, Error> {
+///         let mut text = String::new();
+///         reader.read_to_string(&mut text)?;
+///         let y: Vec<f64> = text.split_whitespace().skip(1).filter_map(|v| v.parse().ok()).collect();
+///         let x = (0..y.len()).map(|i| i as f64).collect();
+///         Ok(vec![Pattern::new(x, y, None)?])
+///     }
+/// }
+///
+/// register_reader(Counts);
+/// let pattern = geddes::read_bytes(b"COUNTS 5 7 9", "scan.cnt").unwrap();
+/// assert_eq!(pattern.y, vec![5.0, 7.0, 9.0]);
+/// ```
+pub trait PatternReader: Send + Sync {
+    /// Short, unique name of the format (e.g. `Xrdml`).
+    fn name(&self) -> &str;
+
+    /// Lowercase file extensions, without the dot, that this format uses.
+    fn extensions(&self) -> &[&str] {
+        &[]
+    }
+
+    /// Returns `true` if a file named with `extension` should be parsed by
+    /// this reader.
+    ///
+    /// Defaults to a lookup in [`extensions`](PatternReader::extensions).
+    /// Override it when several formats share an extension and the content
+    /// has to decide.
+    fn claims_extension(&self, extension: &str, reader: &mut dyn ReadSeek) -> Result<bool, Error> {
+        let _ = reader;
+        Ok(self.extensions().contains(&extension))
+    }
+
+    /// Returns a confidence between 0 and 1 that the content is in this
+    /// format, or `None` if it is not.
+    fn detect(&self, reader: &mut dyn ReadSeek) -> Result<Option<f64>, Error>;
+
+    /// Parses every pattern stored in the source, in file order.
+    fn parse_all(
+        &self,
+        reader: &mut dyn ReadSeek,
+        options: &ReadOptions,
+    ) -> Result<Vec<Pattern>, Error>;
+
+    /// Parses the first pattern stored in the source.
+    ///
+    /// Defaults to the first result of [`parse_all`](PatternReader::parse_all).
+    fn parse(&self, reader: &mut dyn ReadSeek, options: &ReadOptions) -> Result<Pattern, Error> {
+        self.parse_all(reader, options)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::parse(format!("No pattern found in {} data", self.name())))
+    }
+
+    /// Parses every pattern stored in the source together with its parse
+    /// warnings.
+    ///
+    /// Defaults to [`parse_all`](PatternReader::parse_all) without warnings.
+    fn parse_all_reports(
+        &self,
+        reader: &mut dyn ReadSeek,
+        options: &ReadOptions,
+    ) -> Result<Vec<ReadReport>, Error> {
+        Ok(self
+            .parse_all(reader, options)?
+            .into_iter()
+            .map(ReadReport::from)
+            .collect())
+    }
+
+    /// Parses the first pattern stored in the source together with its parse
+    /// warnings.
+    ///
+    /// Defaults to [`parse`](PatternReader::parse) without warnings.
+    fn parse_report(
+        &self,
+        reader: &mut dyn ReadSeek,
+        options: &ReadOptions,
+    ) -> Result<ReadReport, Error> {
+        self.parse(reader, options).map(ReadReport::from)
+    }
+}
+
+/// Detection confidence from which content overrides the file extension.
+///
+/// Magic bytes, ZIP entries, XML root elements and GSAS `BANK` headers reach
+/// it; plain numeric columns do not.
+const OVERRIDE_CONFIDENCE: f64 = 0.9;
+
+/// [`PatternReader`] for one of the built-in [`Format`]s.
+#[derive(Clone, Copy)]
+struct BuiltinReader(Format);
+
+impl BuiltinReader {
+    /// Decides whether the format claims `extension`; `binary` is only asked
+    /// for the extensions that need a look at the content.
+    fn claims_with(
+        &self,
+        extension: &str,
+        binary: impl FnOnce() -> Result<bool, Error>,
+    ) -> Result<bool, Error> {
+        // `.raw` is shared by GSAS (text) and Bruker (binary) files, so the
+        // content decides between the two.
+        match (self.0, extension) {
+            (Format::GsasRaw, "raw") => Ok(!binary()?),
+            (Format::BrukerRaw, "raw") => binary(),
+            _ => Ok(self.extensions().contains(&extension)),
+        }
+    }
+
+    fn confidence(&self, detection: Option<FormatDetection>) -> Option<f64> {
+        detection
+            .filter(|detection| detection.format == self.0)
+            .map(|detection| detection.confidence)
+    }
+}
+
+/// The content checks shared by the built-in readers, each run at most once
+/// per input.
+#[derive(Default)]
+struct Sniff {
+    detection: Option<Option<FormatDetection>>,
+    binary: Option<bool>,
+}
+
+impl Sniff {
+    fn detection(
+        &mut self,
+        mut reader: &mut dyn ReadSeek,
+    ) -> Result<Option<FormatDetection>, Error> {
+        if let Some(detection) = self.detection {
+            return Ok(detection);
+        }
+        reader.seek(SeekFrom::Start(0))?;
+        let detection = detect_format(&mut reader)?;
+        self.detection = Some(detection);
+        Ok(detection)
+    }
+
+    fn binary(&mut self, reader: &mut dyn ReadSeek) -> Result<bool, Error> {
+        if let Some(binary) = self.binary {
+            return Ok(binary);
+        }
+        reader.seek(SeekFrom::Start(0))?;
+        let binary = is_binary(reader)?;
+        self.binary = Some(binary);
+        Ok(binary)
+    }
+}
+
+/// A registered reader; built-in ones keep their format so that they can
+/// share a single [`Sniff`] of the input.
+#[derive(Clone)]
+struct Entry {
+    reader: Arc<dyn PatternReader>,
+    builtin: Option<BuiltinReader>,
+}
+
+impl Entry {
+    fn claims_extension(
+        &self,
+        extension: &str,
+        reader: &mut dyn ReadSeek,
+        sniff: &mut Sniff,
+    ) -> Result<bool, Error> {
+        match &self.builtin {
+            Some(builtin) => builtin.claims_with(extension, || sniff.binary(reader)),
+            None => {
+                reader.seek(SeekFrom::Start(0))?;
+                self.reader.claims_extension(extension, reader)
+            }
+        }
+    }
+
+    fn detect(&self, reader: &mut dyn ReadSeek, sniff: &mut Sniff) -> Result<Option<f64>, Error> {
+        match &self.builtin {
+            Some(builtin) => Ok(builtin.confidence(sniff.detection(reader)?)),
+            None => {
+                reader.seek(SeekFrom::Start(0))?;
+                self.reader.detect(reader)
+            }
+        }
+    }
+}
+
+impl PatternReader for BuiltinReader {
+    fn name(&self) -> &str {
+        match self.0 {
+            Format::GsasRaw => "GsasRaw",
+            Format::BrukerRaw => "BrukerRaw",
+            Format::Rasx => "Rasx",
+            Format::Xrdml => "Xrdml",
+            Format::Xy => "Xy",
+            Format::Csv => "Csv",
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        match self.0 {
+            Format::GsasRaw => &["raw", "gsa", "gss"],
+            Format::BrukerRaw => &["raw"],
+            Format::Rasx => &["rasx"],
+            Format::Xrdml => &["xrdml"],
+            Format::Xy => &["xy", "xye"],
+            Format::Csv => &["csv"],
+        }
+    }
+
+    fn claims_extension(&self, extension: &str, reader: &mut dyn ReadSeek) -> Result<bool, Error> {
+        self.claims_with(extension, || is_binary(reader))
+    }
+
+    fn detect(&self, mut reader: &mut dyn ReadSeek) -> Result<Option<f64>, Error> {
+        Ok(self.confidence(detect_format(&mut reader)?))
+    }
+
+    fn parse_all(
+        &self,
+        reader: &mut dyn ReadSeek,
+        options: &ReadOptions,
+    ) -> Result<Vec<Pattern>, Error> {
+        Ok(self
+            .parse_all_reports(reader, options)?
+            .into_iter()
+            .map(|report| report.pattern)
+            .collect())
+    }
+
+    fn parse(&self, reader: &mut dyn ReadSeek, options: &ReadOptions) -> Result<Pattern, Error> {
+        Ok(self.parse_report(reader, options)?.pattern)
+    }
+
+    fn parse_all_reports(
+        &self,
+        reader: &mut dyn ReadSeek,
+        options: &ReadOptions,
+    ) -> Result<Vec<ReadReport>, Error> {
+        Ok(parse_format_all(reader, self.0, options)?
+            .into_iter()
+            .map(|data| finish_report(data, options))
+            .collect())
+    }
+
+    fn parse_report(
+        &self,
+        reader: &mut dyn ReadSeek,
+        options: &ReadOptions,
+    ) -> Result<ReadReport, Error> {
+        Ok(finish_report(
+            parse_format(reader, self.0, options)?,
+            options,
+        ))
+    }
+}
+
+/// An ordered set of [`PatternReader`]s used to pick a parser for a file.
+///
+/// A reader is chosen by file extension first and by content detection when
+/// no reader claims the extension, when another reader recognizes the content
+/// with high confidence, or when the claimed reader fails or finds no data.
+/// Readers registered later take precedence over earlier ones, so a custom
+/// reader can replace a built-in format for its extensions.
+///
+/// [`read`](crate::read) and the other `read_*` functions use a process-wide
+/// registry that is extended with [`register_reader`]; build a
+/// `FormatRegistry` directly to keep custom readers local.
+#[derive(Clone)]
+pub struct FormatRegistry {
+    readers: Vec<Entry>,
+}
+
+impl FormatRegistry {
+    /// Creates a registry holding the built-in formats.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        for format in [
+            Format::GsasRaw,
+            Format::BrukerRaw,
+            Format::Rasx,
+            Format::Xrdml,
+            Format::Xy,
+            Format::Csv,
+        ] {
+            registry.readers.push(Entry {
+                reader: Arc::new(BuiltinReader(format)),
+                builtin: Some(BuiltinReader(format)),
+            });
+        }
+        registry
+    }
+
+    /// Creates a registry without any reader.
+    pub fn empty() -> Self {
+        Self {
+            readers: Vec::new(),
+        }
+    }
+
+    /// Adds a reader, giving it precedence over the ones already registered.
+    pub fn register<T: PatternReader + 'static>(&mut self, reader: T) {
+        self.readers.push(Entry {
+            reader: Arc::new(reader),
+            builtin: None,
+        });
+    }
+
+    /// Names of the registered readers, highest precedence first.
+    pub fn names(&self) -> Vec<&str> {
+        self.by_precedence()
+            .map(|entry| entry.reader.name())
+            .collect()
+    }
+
+    /// Load a pattern from a file path. See [`read`](crate::read).
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> Result<Pattern, Error> {
+        let path = path.as_ref();
+        let filename = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+        self.read_reader(File::open(path)?, filename)
+    }
+
+    /// Load a pattern from a reader with a filename hint.
+    pub fn read_reader<R: Read + Seek>(&self, reader: R, filename: &str) -> Result<Pattern, Error> {
+        self.read_reader_with(reader, filename, &ReadOptions::new())
+    }
+
+    /// Load a pattern from a reader with a filename hint and parser options.
+    pub fn read_reader_with<R: Read + Seek>(
+        &self,
+        reader: R,
+        filename: &str,
+        options: &ReadOptions,
+    ) -> Result<Pattern, Error> {
+        Ok(self.read_report_reader(reader, filename, options)?.pattern)
+    }
+
+    /// Load a pattern and its parse warnings from a reader with a filename
+    /// hint. See [`read_report`](crate::read_report).
+    pub fn read_report_reader<R: Read + Seek>(
+        &self,
+        mut reader: R,
+        filename: &str,
+        options: &ReadOptions,
+    ) -> Result<ReadReport, Error> {
+        self.dispatch(
+            &mut reader,
+            filename,
+            |format, reader| format.parse_report(reader, options),
+            |report| !report.pattern.x.is_empty(),
+        )
+    }
+
+    /// Load a pattern from in-memory bytes with a filename hint.
+    pub fn read_bytes<B: AsRef<[u8]>>(&self, bytes: B, filename: &str) -> Result<Pattern, Error> {
+        self.read_reader(Cursor::new(bytes.as_ref()), filename)
+    }
+
+    /// Load every pattern stored in a file. See [`read_all`](crate::read_all).
+    pub fn read_all<P: AsRef<Path>>(&self, path: P) -> Result<Vec<Pattern>, Error> {
+        let path = path.as_ref();
+        let filename = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+        self.read_all_reader(File::open(path)?, filename)
+    }
+
+    /// Load every pattern from a reader with a filename hint.
+    pub fn read_all_reader<R: Read + Seek>(
+        &self,
+        reader: R,
+        filename: &str,
+    ) -> Result<Vec<Pattern>, Error> {
+        self.read_all_reader_with(reader, filename, &ReadOptions::new())
+    }
+
+    /// Load every pattern from a reader with a filename hint and parser
+    /// options.
+    pub fn read_all_reader_with<R: Read + Seek>(
+        &self,
+        mut reader: R,
+        filename: &str,
+        options: &ReadOptions,
+    ) -> Result<Vec<Pattern>, Error> {
+        let reports = self.dispatch(
+            &mut reader,
+            filename,
+            |format, reader| format.parse_all_reports(reader, options),
+            |reports| reports.iter().any(|report| !report.pattern.x.is_empty()),
+        )?;
+        Ok(reports.into_iter().map(|report| report.pattern).collect())
+    }
+
+    /// Load every pattern from in-memory bytes with a filename hint.
+    pub fn read_all_bytes<B: AsRef<[u8]>>(
+        &self,
+        bytes: B,
+        filename: &str,
+    ) -> Result<Vec<Pattern>, Error> {
+        self.read_all_reader(Cursor::new(bytes.as_ref()), filename)
+    }
+
+    /// Returns the name and confidence of the reader that best matches the
+    /// content, ignoring the file name.
+    pub fn detect<R: Read + Seek>(&self, mut reader: R) -> Result<Option<(&str, f64)>, Error> {
+        let detections = self.detect_all(&mut reader, &mut Sniff::default())?;
+        Ok(
+            best_detection(&detections)
+                .map(|(entry, confidence)| (entry.reader.name(), confidence)),
+        )
+    }
+
+    fn by_precedence(&self) -> impl Iterator<Item = &Entry> {
+        self.readers.iter().rev()
+    }
+
+    /// Picks a reader by extension, falling back to content detection, and
+    /// retries with the detected reader if the first one fails or finds no
+    /// data.
+    ///
+    /// A detection of at least [`OVERRIDE_CONFIDENCE`] by another reader
+    /// overrides the extension, unless the claiming reader recognizes the
+    /// content just as confidently.
+    fn dispatch<T>(
+        &self,
+        reader: &mut dyn ReadSeek,
+        filename: &str,
+        parse: impl Fn(&dyn PatternReader, &mut dyn ReadSeek) -> Result<T, Error>,
+        has_data: impl Fn(&T) -> bool,
+    ) -> Result<T, Error> {
+        let mut sniff = Sniff::default();
+        let detections = self.detect_all(reader, &mut sniff)?;
+        let detected = best_detection(&detections);
+        let claimed = self.claim_extension(reader, filename, &mut sniff)?;
+        let format = match (claimed, detected) {
+            (Some(claimed), Some((detected, confidence)))
+                if confidence >= OVERRIDE_CONFIDENCE && !same_entry(claimed, detected) =>
+            {
+                let claimed_confidence = detections
+                    .iter()
+                    .find(|(entry, _)| same_entry(entry, claimed))
+                    .map_or(0.0, |&(_, confidence)| confidence);
+                if claimed_confidence < confidence {
+                    detected
+                } else {
+                    claimed
+                }
+            }
+            (Some(claimed), _) => claimed,
+            (None, Some((detected, _))) => detected,
+            (None, None) => return Err(Error::UnknownFormat),
+        };
+
+        reader.seek(SeekFrom::Start(0))?;
+        let result = parse(format.reader.as_ref(), reader);
+        if matches!(&result, Ok(data) if has_data(data)) {
+            return result;
+        }
+        // The extension may be wrong: retry with the format the content
+        // suggests, keeping the first outcome if the retry does no better.
+        match detected {
+            Some((detected, _)) if !same_entry(detected, format) => {
+                reader.seek(SeekFrom::Start(0))?;
+                match parse(detected.reader.as_ref(), reader) {
+                    Ok(data) if has_data(&data) => Ok(data),
+                    _ => result,
+                }
+            }
+            _ => result,
+        }
+    }
+
+    /// Returns the first reader, by precedence, that claims the extension of
+    /// `filename`.
+    fn claim_extension(
+        &self,
+        reader: &mut dyn ReadSeek,
+        filename: &str,
+        sniff: &mut Sniff,
+    ) -> Result<Option<&Entry>, Error> {
+        let extension = file_extension(filename);
+        if extension.is_empty() {
+            return Ok(None);
+        }
+        for entry in self.by_precedence() {
+            if entry.claims_extension(&extension, reader, sniff)? {
+                return Ok(Some(entry));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the readers that recognize the content with their confidence,
+    /// highest precedence first.
+    fn detect_all(
+        &self,
+        reader: &mut dyn ReadSeek,
+        sniff: &mut Sniff,
+    ) -> Result<Vec<(&Entry, f64)>, Error> {
+        let mut detections = Vec::new();
+        for entry in self.by_precedence() {
+            if let Some(confidence) = entry.detect(reader, sniff)? {
+                detections.push((entry, confidence));
+            }
+        }
+        reader.seek(SeekFrom::Start(0))?;
+        Ok(detections)
+    }
+}
+
+impl Default for FormatRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for FormatRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FormatRegistry")
+            .field("readers", &self.names())
+            .finish()
+    }
+}
+
+/// Returns the detection with the highest confidence; ties go to the reader
+/// with higher precedence.
+fn best_detection<'a>(detections: &[(&'a Entry, f64)]) -> Option<(&'a Entry, f64)> {
+    let mut best: Option<(&Entry, f64)> = None;
+    for &(entry, confidence) in detections {
+        if best.is_none_or(|(_, best)| confidence > best) {
+            best = Some((entry, confidence));
+        }
+    }
+    best
+}
+
+fn same_entry(a: &Entry, b: &Entry) -> bool {
+    Arc::ptr_eq(&a.reader, &b.reader)
+}
+
+fn global_registry() -> &'static RwLock<FormatRegistry> {
+    static REGISTRY: OnceLock<RwLock<FormatRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(FormatRegistry::new()))
+}
+
+/// Adds a reader to the registry used by [`read`](crate::read) and the other
+/// `read_*` functions.
+///
+/// The reader takes precedence over the built-in formats and any reader
+/// registered before it.
+pub fn register_reader<T: PatternReader + 'static>(reader: T) {
+    global_registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .register(reader);
+}
+
+/// Returns a snapshot of the registry used by [`read`](crate::read).
+pub fn registry() -> FormatRegistry {
+    global_registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
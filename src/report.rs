@@ -0,0 +1,106 @@
+use crate::error::Location;
+use crate::Pattern;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Number of occurrences whose location is kept per [`Warning`].
+const MAX_LOCATIONS: usize = 20;
+
+/// A pattern together with the problems the parser worked around.
+///
+/// Lenient parsing skips malformed lines and values instead of failing; the
+/// warnings record what was skipped so callers can surface it.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReadReport {
+    /// The loaded pattern.
+    pub pattern: Pattern,
+    /// Non-fatal problems, one entry per kind in order of first occurrence.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
+}
+
+impl From<Pattern> for ReadReport {
+    fn from(pattern: Pattern) -> Self {
+        ReadReport {
+            pattern,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// The kind of a non-fatal parse problem.
+///
+/// More kinds may be added, so matches need a wildcard arm.
+#[cfg_attr(feature = "python", pyclass(eq, eq_int, from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// A data line without a numeric (x, y) pair was skipped.
+    SkippedLine,
+    /// A data line has a different number of columns than the first one, so
+    /// the error column was dropped.
+    InconsistentColumns,
+    /// A value that is not a number was skipped.
+    InvalidValue,
+    /// The text starts with a byte order mark, which was skipped.
+    ByteOrderMark,
+    /// Optional metadata could not be read and was skipped.
+    InvalidMetadata,
+}
+
+/// A non-fatal parse problem and where it occurred.
+#[cfg_attr(feature = "python", pyclass(get_all, skip_from_py_object))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Warning {
+    /// What kind of problem this is.
+    pub kind: WarningKind,
+    /// Description of the first occurrence.
+    pub message: String,
+    /// Number of occurrences.
+    pub count: usize,
+    /// Locations of the first occurrences (at most 20), for line-based text.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<Location>,
+}
+
+/// Collects warnings while parsing, merging repeated kinds.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct WarningLog {
+    warnings: Vec<Warning>,
+}
+
+impl WarningLog {
+    /// Records one occurrence of `kind`; `message` is kept for the first.
+    pub(crate) fn add(
+        &mut self,
+        kind: WarningKind,
+        message: impl Into<String>,
+        location: Option<Location>,
+    ) {
+        let index = match self.warnings.iter().position(|w| w.kind == kind) {
+            Some(index) => index,
+            None => {
+                self.warnings.push(Warning {
+                    kind,
+                    message: message.into(),
+                    count: 0,
+                    locations: Vec::new(),
+                });
+                self.warnings.len() - 1
+            }
+        };
+        let warning = &mut self.warnings[index];
+        warning.count += 1;
+        if let Some(location) = location {
+            if warning.locations.len() < MAX_LOCATIONS {
+                warning.locations.push(location);
+            }
+        }
+    }
+
+    pub(crate) fn into_vec(self) -> Vec<Warning> {
+        self.warnings
+    }
+}
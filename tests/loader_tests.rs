@@ -1,7 +1,92 @@
-use geddes::{read, read_bytes};
-use std::fs::read as fs_read;
+// The original loader tests check for data with `len() > 0`.
+#![allow(clippy::len_zero)]
+
+use geddes::{
+    detect_format, diagnose_bruker_raw, diagnose_bruker_raw_bytes, read, read_all, read_all_bytes,
+    read_all_bytes_with, read_bytes, read_bytes_with, read_report, read_report_bytes, read_with,
+    read_with_format, read_xrdml_map, read_xrdml_map_bytes, register_reader, Error, Format,
+    FormatRegistry, Location, Pattern, PatternReader, ReadOptions, ReadSeek, WarningKind,
+};
+use std::fs::{read as fs_read, File};
+use std::io::{Cursor, Write};
 use std::path::PathBuf;
 use std::time::Instant;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Builds an in-memory zip archive from `(name, contents)` entries.
+fn build_zip(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in entries {
+        writer
+            .start_file(*name, SimpleFileOptions::default())
+            .expect("Failed to start zip entry");
+        writer
+            .write_all(contents.as_bytes())
+            .expect("Failed to write zip entry");
+    }
+    writer.finish().expect("Failed to finish zip").into_inner()
+}
+
+/// Writes `bytes` at `offset`, growing `buf` with zeros as needed.
+fn put(buf: &mut Vec<u8>, offset: usize, bytes: &[u8]) {
+    if buf.len() < offset + bytes.len() {
+        buf.resize(offset + bytes.len(), 0);
+    }
+    buf[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+/// Builds a Bruker RAW v1 file with two ranges, the second repeating the
+/// `RAW ` magic.
+fn build_bruker_raw1() -> Vec<u8> {
+    let mut v1 = Vec::new();
+    put(&mut v1, 0, b"RAW ");
+    let mut off = 4;
+    for (start, values, following) in [
+        (10.0f32, [1.0f32, 2.0, 3.0], 1u32),
+        (40.0, [7.0, 8.0, 9.0], 0),
+    ] {
+        if off > 4 {
+            put(&mut v1, off, b"RAW ");
+            off += 4;
+        }
+        put(&mut v1, off, &3u32.to_le_bytes());
+        put(&mut v1, off + 4, &2.5f32.to_le_bytes());
+        put(&mut v1, off + 8, &0.5f32.to_le_bytes());
+        put(&mut v1, off + 20, &start.to_le_bytes());
+        put(&mut v1, off + 24, &(-1.0e6f32).to_le_bytes());
+        put(&mut v1, off + 36, b"quartz");
+        put(&mut v1, off + 68, &1.5406f32.to_le_bytes());
+        put(&mut v1, off + 148, &following.to_le_bytes());
+        for (i, value) in values.iter().enumerate() {
+            put(&mut v1, off + 152 + i * 4, &value.to_le_bytes());
+        }
+        off += 152 + 12;
+    }
+    v1
+}
+
+/// Builds a Bruker RAW v2 file with two ranges.
+fn build_bruker_raw2() -> Vec<u8> {
+    let mut v2 = Vec::new();
+    put(&mut v2, 0, b"RAW2");
+    put(&mut v2, 4, &2u16.to_le_bytes());
+    put(&mut v2, 188, b"Cu");
+    put(&mut v2, 190, &1.5406f32.to_le_bytes());
+    let mut off = 256;
+    for (start, values) in [(5.0f32, [4.0f32, 5.0]), (20.0, [6.0, 7.0])] {
+        put(&mut v2, off, &64u16.to_le_bytes());
+        put(&mut v2, off + 2, &2u16.to_le_bytes());
+        put(&mut v2, off + 8, &1.0f32.to_le_bytes());
+        put(&mut v2, off + 12, &0.25f32.to_le_bytes());
+        put(&mut v2, off + 16, &start.to_le_bytes());
+        for (i, value) in values.iter().enumerate() {
+            put(&mut v2, off + 64 + i * 4, &value.to_le_bytes());
+        }
+        off += 64 + 8;
+    }
+    v2
+}
 
 #[test]
 fn test_01_read_gsas_raw() {
@@ -193,3 +278,1414 @@ fn test_14_bruker_raw_diffrac_eva_loads_with_axis() {
         "Too many subnormal intensity values: ratio={ratio}"
     );
 }
+
+#[test]
+fn test_15_xrdml_metadata() {
+    let path = PathBuf::from("tests/data/xrdml/sample.xrdml");
+    let pattern = read(&path).expect("Failed to load xrdml file");
+    let meta = &pattern.metadata;
+    assert_eq!(meta.anode.as_deref(), Some("Cu"));
+    assert_eq!(meta.wavelength, Some(1.540598));
+    assert_eq!(meta.goniometer_radius, Some(240.0));
+    assert_eq!(meta.counting_time, Some(39.27));
+    assert_eq!(meta.operator.as_deref(), Some("XRD"));
+    assert_eq!(meta.instrument.as_deref(), Some("EMPYREAN"));
+    assert_eq!(
+        meta.start_time.as_deref(),
+        Some("2021-03-16T13:10:14+03:00")
+    );
+    assert_eq!(meta.end_time.as_deref(), Some("2021-03-16T13:24:36+03:00"));
+}
+
+#[test]
+fn test_16_rasx_metadata() {
+    let path = PathBuf::from("tests/data/rasx/sample.rasx");
+    let pattern = read(&path).expect("Failed to load rasx file");
+    let meta = &pattern.metadata;
+    assert_eq!(meta.operator.as_deref(), Some("Yudan"));
+    assert_eq!(meta.instrument.as_deref(), Some("SmartLabXE"));
+    assert_eq!(meta.anode.as_deref(), Some("Cu"));
+    assert_eq!(meta.wavelength, Some(1.540593));
+    assert_eq!(meta.start_time.as_deref(), Some("2025-09-19T19:57:59Z"));
+    let counting_time = meta.counting_time.expect("Missing counting time");
+    assert!((counting_time - 0.9).abs() < 1e-9);
+}
+
+#[test]
+fn test_17_bruker_raw_metadata() {
+    let path = PathBuf::from("tests/data/bruker_raw/bruker4_v5converter.raw");
+    let pattern = read(&path).expect("Failed to load Bruker raw file");
+    let meta = &pattern.metadata;
+    assert_eq!(meta.operator.as_deref(), Some("Yanyan"));
+    assert_eq!(
+        meta.sample_name.as_deref(),
+        Some("Cu-12%Ag_500C1700h_P5n5rpm1_RT")
+    );
+    assert_eq!(meta.anode.as_deref(), Some("Cu"));
+    assert_eq!(meta.start_time.as_deref(), Some("2021-08-30T20:35:58"));
+    assert_eq!(
+        meta.extra.get("CREATOR").map(String::as_str),
+        Some("V5Converter")
+    );
+    let wavelength = meta.wavelength.expect("Missing wavelength");
+    assert!((wavelength - 1.5406).abs() < 1e-6);
+}
+
+#[test]
+fn test_18_gsas_raw_metadata() {
+    let path = PathBuf::from("tests/data/gsas_raw/gsas.raw");
+    let pattern = read(&path).expect("Failed to load raw file");
+    assert_eq!(pattern.metadata.title.as_deref(), Some("Co"));
+    assert!(read_bytes(b"1.0 2.0\n", "data.xy")
+        .expect("Failed to load xy bytes")
+        .metadata
+        .is_empty());
+}
+
+#[test]
+fn test_19_xrdml_used_wavelength() {
+    let path = PathBuf::from("tests/data/xrdml/sample.xrdml");
+    let pattern = read(&path).expect("Failed to load xrdml file");
+    let radiation = pattern
+        .metadata
+        .radiation
+        .as_ref()
+        .expect("Missing usedWavelength");
+    assert_eq!(radiation.intended.as_deref(), Some("K-Alpha 1"));
+    assert_eq!(radiation.k_alpha1, Some(1.540598));
+    assert_eq!(radiation.k_alpha2, Some(1.544426));
+    assert_eq!(radiation.k_beta, Some(1.39225));
+    assert_eq!(radiation.ratio_k_alpha2_k_alpha1, Some(0.5));
+    assert_eq!(radiation.unit.as_deref(), Some("Angstrom"));
+    let average = radiation.k_alpha_average().expect("Missing average");
+    assert!((average - (1.540598 + 0.5 * 1.544426) / 1.5).abs() < 1e-12);
+}
+
+#[test]
+fn test_20_xrdml_wavelength_unit_conversion() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xrdMeasurements><xrdMeasurement>
+<usedWavelength intended="K-Alpha 1">
+<kAlpha1 unit="nm">0.15405980</kAlpha1>
+<kAlpha2 unit="nm">0.15444260</kAlpha2>
+</usedWavelength>
+<scan><dataPoints>
+<positions axis="2Theta" unit="deg"><startPosition>10</startPosition><endPosition>11</endPosition></positions>
+<intensities unit="counts">1 2 3</intensities>
+</dataPoints></scan>
+</xrdMeasurement></xrdMeasurements>"#;
+    let pattern = read_bytes(xml, "nm.xrdml").expect("Failed to load xrdml bytes");
+    let radiation = pattern
+        .metadata
+        .radiation
+        .as_ref()
+        .expect("Missing radiation");
+    assert_eq!(radiation.unit.as_deref(), Some("nm"));
+    assert_eq!(radiation.k_alpha1, Some(0.1540598));
+    let wavelength = pattern.metadata.wavelength.expect("Missing wavelength");
+    assert!((wavelength - 1.540598).abs() < 1e-9);
+}
+
+#[test]
+fn test_21_rasx_measurement_conditions() {
+    let path = PathBuf::from("tests/data/rasx/sample.rasx");
+    let pattern = read(&path).expect("Failed to load rasx file");
+    let meta = &pattern.metadata;
+    assert_eq!(
+        meta.extra.get("RootVersion").map(String::as_str),
+        Some("1.1.0.0")
+    );
+
+    let conditions = meta.conditions.as_ref().expect("Missing conditions");
+    assert_eq!(conditions.detector.as_deref(), Some("HyPix3000(H)"));
+    assert_eq!(conditions.geometry.as_deref(), Some("BB"));
+    assert_eq!(
+        conditions.hardware.get("IncidentCBO").map(String::as_str),
+        Some("CBO-E")
+    );
+    let generator = conditions.generator.as_ref().expect("Missing generator");
+    assert_eq!(generator.target.as_deref(), Some("Cu"));
+    assert_eq!(generator.voltage, Some(40.0));
+    assert_eq!(generator.current, Some(50.0));
+    let two_theta = conditions
+        .axes
+        .iter()
+        .find(|axis| axis.name == "TwoTheta")
+        .expect("Missing TwoTheta axis");
+    assert_eq!(two_theta.state.as_deref(), Some("Scan"));
+    assert_eq!(two_theta.offset, Some(1.0824));
+
+    let scan = meta.scan.as_ref().expect("Missing scan settings");
+    assert_eq!(scan.axis.as_deref(), Some("TwoTheta"));
+    assert_eq!(scan.mode.as_deref(), Some("CONTINUOUS"));
+    assert_eq!(scan.start, Some(10.0));
+    assert_eq!(scan.end, Some(80.0));
+    assert_eq!(scan.step, Some(0.03));
+    assert_eq!(scan.speed_unit.as_deref(), Some("deg/min"));
+}
+
+#[test]
+fn test_22_rasx_attenuation_factors() {
+    let bytes = build_zip(&[(
+        "Data0/Profile0.txt",
+        "10.00\t100.0\t1\r\n10.01\t50.0\t10.5\r\n10.02\t80.0\t1\r\n",
+    )]);
+    let mut pattern = read_bytes(&bytes, "attenuated.rasx").expect("Failed to load rasx");
+    assert_eq!(pattern.y, vec![100.0, 50.0, 80.0]);
+    assert_eq!(pattern.attenuation, Some(vec![1.0, 10.5, 1.0]));
+
+    pattern.apply_attenuation();
+    assert_eq!(pattern.y, vec![100.0, 525.0, 80.0]);
+    assert!(pattern.attenuation.is_none());
+
+    let sample = read("tests/data/rasx/sample.rasx").expect("Failed to load rasx file");
+    let factors = sample.attenuation.expect("Missing attenuation column");
+    assert_eq!(factors.len(), sample.y.len());
+}
+
+#[test]
+fn test_23_rasx_read_all_data_sets() {
+    let conditions = |operator: &str| {
+        format!(
+            "<MeasurementConditions><GeneralInformation><Operator>{operator}</Operator>\
+             </GeneralInformation></MeasurementConditions>"
+        )
+    };
+    let first = conditions("first");
+    let second = conditions("second");
+    let bytes = build_zip(&[
+        ("Data0/Profile0.txt", "10.0 1.0 1\n10.1 2.0 1\n"),
+        ("Data0/MesurementConditions0.xml", &first),
+        ("Data1/Profile1.txt", "20.0 3.0 1\n20.1 4.0 1\n20.2 5.0 1\n"),
+        ("Data1/MesurementConditions1.xml", &second),
+    ]);
+
+    let patterns = read_all_bytes(&bytes, "multi.rasx").expect("Failed to load rasx");
+    assert_eq!(patterns.len(), 2);
+    assert_eq!(patterns[0].x, vec![10.0, 10.1]);
+    assert_eq!(patterns[1].y, vec![3.0, 4.0, 5.0]);
+    assert_eq!(patterns[0].metadata.operator.as_deref(), Some("first"));
+    assert_eq!(patterns[1].metadata.operator.as_deref(), Some("second"));
+    assert_eq!(
+        patterns[1]
+            .metadata
+            .extra
+            .get("Profile")
+            .map(String::as_str),
+        Some("Data1/Profile1.txt")
+    );
+
+    let single = read_bytes(&bytes, "multi.rasx").expect("Failed to load rasx");
+    assert_eq!(single.x, patterns[0].x);
+
+    let xy = read_all("tests/data/xy/sample.xy").expect("Failed to load xy file");
+    assert_eq!(xy.len(), 1);
+}
+
+#[test]
+fn test_24_xrdml_multiple_scans() {
+    let scan = |number: u32, start: f64, status: &str, counts: &str| {
+        format!(
+            r#"<scan appendNumber="{number}" mode="Continuous" scanAxis="Gonio" status="{status}">
+<header><startTimeStamp>2024-01-01T00:0{number}:00</startTimeStamp></header>
+<dataPoints>
+<positions axis="2Theta" unit="deg"><startPosition>{start}</startPosition><endPosition>{end}</endPosition></positions>
+<intensities unit="counts">{counts}</intensities>
+</dataPoints></scan>"#,
+            end = start + 1.0
+        )
+    };
+    let xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<xrdMeasurements><sample><name>S1</name></sample>
+<xrdMeasurement measurementType="Scan">
+<usedWavelength><kAlpha1 unit="Angstrom">1.54</kAlpha1></usedWavelength>
+{}{}
+</xrdMeasurement>
+<xrdMeasurement measurementType="Scan">
+<usedWavelength><kAlpha1 unit="Angstrom">0.71</kAlpha1></usedWavelength>
+{}
+</xrdMeasurement></xrdMeasurements>"#,
+        scan(0, 10.0, "Completed", "1 2 3"),
+        scan(1, 20.0, "Completed", "4 5"),
+        scan(0, 30.0, "Aborted", "6 7 8 9"),
+    );
+
+    let patterns = read_all_bytes(&xml, "batch.xrdml").expect("Failed to load xrdml");
+    assert_eq!(patterns.len(), 3);
+    assert_eq!(patterns[0].x, vec![10.0, 10.5, 11.0]);
+    assert_eq!(patterns[1].y, vec![4.0, 5.0]);
+    assert_eq!(patterns[2].x.first(), Some(&30.0));
+
+    let scans: Vec<_> = patterns
+        .iter()
+        .map(|p| p.metadata.scan.clone().expect("Missing scan info"))
+        .collect();
+    assert_eq!(scans[0].index, Some(0));
+    assert_eq!(scans[1].index, Some(1));
+    assert_eq!(scans[1].mode.as_deref(), Some("Continuous"));
+    assert_eq!(scans[2].status.as_deref(), Some("Aborted"));
+
+    assert!(patterns
+        .iter()
+        .all(|p| p.metadata.sample_name.as_deref() == Some("S1")));
+    assert_eq!(patterns[1].metadata.wavelength, Some(1.54));
+    assert_eq!(patterns[2].metadata.wavelength, Some(0.71));
+    assert_eq!(
+        patterns[1].metadata.start_time.as_deref(),
+        Some("2024-01-01T00:01:00")
+    );
+
+    let first = read_bytes(&xml, "batch.xrdml").expect("Failed to load xrdml");
+    assert_eq!(first.x, patterns[0].x);
+}
+
+#[test]
+fn test_25_read_all_gsas_banks() {
+    let raw = "\
+Two banks
+BANK 1 3 1 CONST 1000.0 10.0 0 0 STD
+  10  20  30
+BANK 2 2 1 CONST 2000.0 5.0 0 0 STD
+  40  50
+";
+
+    let patterns = read_all_bytes(raw, "banks.raw").expect("Failed to load gsas");
+    assert_eq!(patterns.len(), 2);
+    assert_eq!(patterns[0].x, vec![10.0, 10.1, 10.2]);
+    assert_eq!(patterns[0].y, vec![10.0, 20.0, 30.0]);
+    assert_eq!(patterns[1].x, vec![20.0, 20.05]);
+    assert_eq!(patterns[1].y, vec![40.0, 50.0]);
+
+    let bank = patterns[1]
+        .metadata
+        .bank
+        .as_ref()
+        .expect("Missing bank header");
+    assert_eq!(bank.number, 2);
+    assert_eq!(bank.channels, 2);
+    assert_eq!(bank.records, 1);
+    assert_eq!(bank.binning, "CONST");
+    assert_eq!(bank.coefficients, vec![2000.0, 5.0, 0.0, 0.0]);
+    assert_eq!(bank.data_type, "STD");
+    assert!(patterns
+        .iter()
+        .all(|p| p.metadata.title.as_deref() == Some("Two banks")));
+
+    let first = read_bytes(raw, "banks.raw").expect("Failed to load gsas");
+    assert_eq!(first.y, patterns[0].y);
+    assert_eq!(first.metadata.bank.map(|b| b.number), Some(1));
+}
+
+#[test]
+fn test_26_gsas_binning_and_layouts() {
+    let esd = format!("{:>8}{:>8}{:>8}{:>8}", "100", "10", "400", "20");
+    let alt = format!(
+        "{:>8}{:>7}{:>5}{:>8}{:>7}{:>5}",
+        "32000", "5.5", "0.5", "32320", "6.5", "0.6"
+    );
+    let raw = format!(
+        "\
+Binning types
+BANK 1 2 1 CONST 1000.0 10.0 0 0 ESD
+{esd}
+BANK 2 3 3 SLOG 1000.0 1210.0 0.1 0 FXYE
+1000.0 1.0 0.1
+1100.0 2.0 0.2
+1210.0 3.0 0.3
+BANK 3 2 1 RALF 32000 320 32000 0.01 ALT
+{alt}
+BANK 4 3 1 LOG6 1000.0 0.1 STD
+  1  2  3
+BANK 5 4 1 TIME_MAP 1 STD
+  1  2  3  4
+TIME_MAP 1 7 1 TIME_MAP 1000
+         1      2000       100         3      2200       200      2600
+"
+    );
+
+    let patterns = read_all_bytes(&raw, "binning.raw").expect("Failed to load gsas");
+    assert_eq!(patterns.len(), 5);
+
+    assert_eq!(patterns[0].x, vec![10.0, 10.1]);
+    assert_eq!(patterns[0].y, vec![100.0, 400.0]);
+    assert_eq!(patterns[0].e, Some(vec![10.0, 20.0]));
+
+    assert_eq!(patterns[1].x, vec![1000.0, 1100.0, 1210.0]);
+    assert_eq!(patterns[1].e, Some(vec![0.1, 0.2, 0.3]));
+    let scan = patterns[1].metadata.scan.as_ref().expect("Missing scan");
+    assert_eq!(scan.axis.as_deref(), Some("TOF"));
+
+    assert_eq!(patterns[2].x, vec![1000.0, 1010.0]);
+    assert_eq!(patterns[2].y, vec![5.5, 6.5]);
+    assert_eq!(patterns[2].e, Some(vec![0.5, 0.6]));
+
+    let log6 = &patterns[3].x;
+    assert_eq!(log6.len(), 3);
+    assert!((log6[1] - 1100.0).abs() < 1e-9);
+    assert!((log6[2] - 1210.0).abs() < 1e-9);
+    assert!(patterns[3].e.is_none());
+
+    assert_eq!(patterns[4].x, vec![2000.0, 2100.0, 2200.0, 2400.0]);
+    assert_eq!(patterns[4].y, vec![1.0, 2.0, 3.0, 4.0]);
+}
+
+#[test]
+fn test_27_gsas_std_counters() {
+    // Fields run together and carry NCTR counters: (I2, F6.0) x 10
+    let raw = "\
+Counters
+BANK 1 4 1 CONST 1000.0 10.0 0 0 STD
+ 4   400 1123456      90 2  1800
+";
+
+    let pattern = read_bytes(raw, "counters.raw").expect("Failed to load gsas");
+    assert_eq!(pattern.y, vec![400.0, 123456.0, 90.0, 1800.0]);
+    let e = pattern.e.expect("Missing errors");
+    assert_eq!(e[0], 10.0);
+    assert_eq!(e[2], 90f64.sqrt());
+    assert_eq!(e[3], 30.0);
+
+    // " 1123456" may also be the intensity 1123456, so it is flagged.
+    let report = read_report_bytes(raw, "counters.raw", &ReadOptions::new()).unwrap();
+    assert_eq!(report.pattern.y[1], 123456.0);
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].kind, WarningKind::InvalidValue);
+    assert!(report.warnings[0]
+        .message
+        .contains("\" 1123456\" is ambiguous"));
+    assert_eq!(report.warnings[0].locations[0].line, 3);
+    let strict = ReadOptions::new().strict(true);
+    let err = read_bytes_with(raw, "counters.raw", &strict).unwrap_err();
+    assert!(err.to_string().contains("ambiguous"));
+
+    let path = PathBuf::from("tests/data/gsas_raw/gsas.raw");
+    let pattern = read(&path).expect("Failed to load raw file");
+    assert_eq!(pattern.y.len(), 4941);
+    assert_eq!(pattern.y[..3], [1163.0, 1092.0, 1079.0]);
+    assert!(pattern.e.is_none());
+}
+
+#[test]
+fn test_28_bruker_raw4_ranges() {
+    let path = PathBuf::from("tests/data/bruker_raw/bruker4_diffrac_eva.raw");
+    let pattern = read(&path).expect("Failed to load Bruker raw file");
+    assert_eq!(pattern.x.len(), 7134);
+    assert_eq!(pattern.x[0], 10.0);
+    let scan = pattern.metadata.scan.as_ref().expect("Missing scan");
+    assert_eq!(scan.mode.as_deref(), Some("Locked Coupled"));
+    assert_eq!(scan.axis.as_deref(), Some("2Theta"));
+    let step = scan.step.expect("Missing step");
+    assert!((step - 0.0105203).abs() < 1e-7);
+    assert!((pattern.x[1] - pattern.x[0] - step).abs() < 1e-12);
+    assert!(pattern.metadata.counting_time.is_some());
+
+    let conditions = pattern
+        .metadata
+        .conditions
+        .as_ref()
+        .expect("Missing conditions");
+    assert_eq!(conditions.detector.as_deref(), Some("LynxEye"));
+    let generator = conditions.generator.as_ref().expect("Missing generator");
+    assert_eq!(generator.voltage, Some(40.0));
+    assert_eq!(generator.current, Some(40.0));
+    let theta = conditions
+        .axes
+        .iter()
+        .find(|axis| axis.name == "Theta")
+        .expect("Missing Theta axis");
+    assert_eq!(theta.position.as_deref(), Some("5"));
+
+    let path = PathBuf::from("tests/data/bruker_raw/bruker4_v5converter.raw");
+    let pattern = read(&path).expect("Failed to load Bruker raw file");
+    assert_eq!(pattern.x.len(), 4059);
+    assert!((pattern.x[0] - 37.0001).abs() < 1e-9);
+    let radiation = pattern
+        .metadata
+        .radiation
+        .as_ref()
+        .expect("Missing radiation");
+    assert!((radiation.k_alpha1.expect("Missing K-alpha1") - 1.5406).abs() < 1e-6);
+}
+
+#[test]
+fn test_29_bruker_legacy_raw() {
+    let v1 = build_bruker_raw1();
+    let pattern = read_bytes(&v1, "v1.raw").expect("Failed to load RAW v1");
+    assert_eq!(pattern.x, vec![10.0, 10.5, 11.0]);
+    assert_eq!(pattern.y, vec![1.0, 2.0, 3.0]);
+    assert_eq!(pattern.metadata.sample_name.as_deref(), Some("quartz"));
+    assert_eq!(pattern.metadata.counting_time, Some(2.5));
+    assert_eq!(
+        pattern
+            .metadata
+            .extra
+            .get("FormatVersion")
+            .map(String::as_str),
+        Some("1")
+    );
+
+    let v2 = build_bruker_raw2();
+    let pattern = read_bytes(&v2, "v2.raw").expect("Failed to load RAW v2");
+    assert_eq!(pattern.x, vec![5.0, 5.25]);
+    assert_eq!(pattern.y, vec![4.0, 5.0]);
+    assert_eq!(pattern.metadata.anode.as_deref(), Some("Cu"));
+    assert!((pattern.metadata.wavelength.expect("Missing wavelength") - 1.5406).abs() < 1e-6);
+
+    // Version 3 (RAW1.01): 712-byte file header, 304-byte range headers.
+    let mut v3 = Vec::new();
+    put(&mut v3, 0, b"RAW1.01");
+    put(&mut v3, 12, &1u32.to_le_bytes());
+    put(&mut v3, 16, b"03/15/2004");
+    put(&mut v3, 26, b"12:30:00");
+    put(&mut v3, 36, b"operator");
+    put(&mut v3, 608, b"Co");
+    put(&mut v3, 624, &1.78897f64.to_le_bytes());
+    let off = 712;
+    put(&mut v3, off, &304u32.to_le_bytes());
+    put(&mut v3, off + 4, &4u32.to_le_bytes());
+    put(&mut v3, off + 8, &15.0f64.to_le_bytes());
+    put(&mut v3, off + 16, &30.0f64.to_le_bytes());
+    put(&mut v3, off + 176, &0.02f64.to_le_bytes());
+    put(&mut v3, off + 192, &1.5f32.to_le_bytes());
+    put(&mut v3, off + 224, &40u32.to_le_bytes());
+    put(&mut v3, off + 228, &30u32.to_le_bytes());
+    put(&mut v3, off + 252, &4u32.to_le_bytes());
+    put(&mut v3, off + 256, &8u32.to_le_bytes());
+    for (i, value) in [10.0f32, 20.0, 30.0, 40.0].iter().enumerate() {
+        put(&mut v3, off + 304 + 8 + i * 4, &value.to_le_bytes());
+    }
+    let pattern = read_bytes(&v3, "v3.raw").expect("Failed to load RAW v3");
+    assert_eq!(pattern.x.len(), 4);
+    assert_eq!(pattern.x[0], 30.0);
+    assert!((pattern.x[3] - 30.06).abs() < 1e-9);
+    assert_eq!(pattern.y, vec![10.0, 20.0, 30.0, 40.0]);
+    let meta = &pattern.metadata;
+    assert_eq!(meta.operator.as_deref(), Some("operator"));
+    assert_eq!(meta.anode.as_deref(), Some("Co"));
+    assert_eq!(meta.start_time.as_deref(), Some("2004-03-15T12:30:00"));
+    assert_eq!(meta.wavelength, Some(1.78897));
+    let generator = meta
+        .conditions
+        .as_ref()
+        .and_then(|c| c.generator.as_ref())
+        .expect("Missing generator");
+    assert_eq!(generator.voltage, Some(40.0));
+    assert_eq!(generator.current, Some(30.0));
+}
+
+#[test]
+fn test_30_bruker_raw_all_ranges() {
+    let patterns = read_all_bytes(build_bruker_raw1(), "v1.raw").expect("Failed to load RAW v1");
+    assert_eq!(patterns.len(), 2);
+    assert_eq!(patterns[1].x, vec![40.0, 40.5, 41.0]);
+    assert_eq!(patterns[1].y, vec![7.0, 8.0, 9.0]);
+
+    let patterns = read_all_bytes(build_bruker_raw2(), "v2.raw").expect("Failed to load RAW v2");
+    assert_eq!(patterns.len(), 2);
+    assert_eq!(patterns[1].x, vec![20.0, 20.25]);
+    assert_eq!(patterns[1].y, vec![6.0, 7.0]);
+
+    // Append a copy of the single RAW4 range with a new start angle.
+    let mut raw4 =
+        fs_read("tests/data/bruker_raw/bruker4_diffrac_eva.raw").expect("Failed to read file");
+    let records_len = u32::from_le_bytes(raw4[56..60].try_into().unwrap()) as usize;
+    let range_offset = 61 + records_len;
+    let mut second = raw4[range_offset..].to_vec();
+    second[0x48..0x50].copy_from_slice(&50.0f64.to_le_bytes());
+    second[0x5c..0x60].copy_from_slice(&2.0f32.to_le_bytes());
+    raw4.extend_from_slice(&second);
+
+    let patterns = read_all_bytes(&raw4, "eva.raw").expect("Failed to load RAW4");
+    assert_eq!(patterns.len(), 2);
+    assert_eq!(patterns[0].x[0], 10.0);
+    assert_eq!(patterns[1].x[0], 50.0);
+    assert_eq!(patterns[0].y, patterns[1].y);
+    assert_eq!(patterns[1].metadata.counting_time, Some(2.0));
+    let scans: Vec<_> = patterns
+        .iter()
+        .map(|p| p.metadata.scan.as_ref().and_then(|scan| scan.index))
+        .collect();
+    assert_eq!(scans, vec![Some(0), Some(1)]);
+
+    let first = read_bytes(&raw4, "eva.raw").expect("Failed to load RAW4");
+    assert_eq!(first.x, patterns[0].x);
+}
+
+#[test]
+fn test_31_bruker_raw_diagnostics() {
+    let path = PathBuf::from("tests/data/bruker_raw/bruker4_diffrac_eva.raw");
+    let report = diagnose_bruker_raw(&path).expect("Failed to diagnose file");
+    assert_eq!(report.version.as_deref(), Some("4"));
+    assert!(!report.heuristic);
+    assert_eq!(report.confidence, 1.0);
+    let selected = report.selected.expect("Missing selected block");
+    assert_eq!(selected.layout, "structured");
+    assert_eq!(selected.count, 7134);
+    assert_eq!(selected.stride, 8);
+    assert_eq!(selected.start, Some(10.0));
+
+    // Break the range header so only the heuristic search can find the data.
+    let mut bytes = fs_read(&path).expect("Failed to read file");
+    let records_len = u32::from_le_bytes(bytes[56..60].try_into().unwrap()) as usize;
+    let datum_offset = 61 + records_len + 0x88;
+    bytes[datum_offset..datum_offset + 4].copy_from_slice(&0u32.to_le_bytes());
+
+    let report = diagnose_bruker_raw_bytes(&bytes).expect("Failed to diagnose bytes");
+    assert!(report.heuristic);
+    let selected = report.selected.as_ref().expect("Missing selected block");
+    assert_eq!(selected.layout, "interleaved");
+    assert_eq!(selected.stride, 8);
+    assert_eq!(selected.start, Some(10.0));
+    assert_eq!(selected.start_offset, Some(61 + records_len + 0x48));
+    assert!(selected.score.is_some());
+    assert!((0.0..=1.0).contains(&report.confidence));
+    assert!(report.rejected.iter().all(|c| c.reason.is_some()));
+
+    let pattern = read_bytes(&bytes, "eva.raw").expect("Failed to load Bruker raw");
+    assert_eq!(pattern.x.len(), selected.count);
+    assert_eq!(pattern.x[0], 10.0);
+}
+
+#[test]
+fn test_32_detect_format() {
+    let cases = [
+        ("tests/data/gsas_raw/gsas.raw", Format::GsasRaw),
+        (
+            "tests/data/bruker_raw/bruker4_v5converter.raw",
+            Format::BrukerRaw,
+        ),
+        ("tests/data/rasx/sample.rasx", Format::Rasx),
+        ("tests/data/xrdml/sample.xrdml", Format::Xrdml),
+        ("tests/data/xy/sample.xy", Format::Xy),
+        // The sample CSV is whitespace separated.
+        ("tests/data/csv/sample.csv", Format::Xy),
+    ];
+    for (path, format) in cases {
+        let bytes = fs_read(path).expect("Failed to read file");
+        let mut cursor = Cursor::new(&bytes);
+        let detection = detect_format(&mut cursor)
+            .expect("Failed to detect format")
+            .unwrap_or_else(|| panic!("No format detected for {path}"));
+        assert_eq!(detection.format, format, "{path}");
+        assert!(detection.confidence > 0.5, "{path}");
+        assert_eq!(cursor.position(), 0);
+
+        // Unknown and misleading names fall back to the content.
+        for name in ["data.txt", "data.dat", "data", "sample.XRDML.bak"] {
+            let pattern = read_bytes(&bytes, name)
+                .unwrap_or_else(|err| panic!("Failed to load {path} as {name}: {err}"));
+            assert!(!pattern.x.is_empty());
+        }
+    }
+
+    let xrdml = fs_read("tests/data/xrdml/sample.xrdml").expect("Failed to read file");
+    let pattern = read_bytes(&xrdml, "mislabeled.xy").expect("Failed to load mislabeled file");
+    assert!(pattern.metadata.wavelength.is_some());
+    let patterns =
+        read_all_bytes(&xrdml, "mislabeled.csv").expect("Failed to load mislabeled file");
+    assert_eq!(patterns.len(), 1);
+
+    let mut cursor = Cursor::new(b"2theta,counts\n10.0,100\n10.1,105\n".to_vec());
+    let detection = detect_format(&mut cursor)
+        .expect("Failed to detect format")
+        .expect("No format detected");
+    assert_eq!(detection.format, Format::Csv);
+
+    let mut cursor = Cursor::new(b"not a diffraction pattern".to_vec());
+    assert!(detect_format(&mut cursor)
+        .expect("Failed to detect")
+        .is_none());
+    assert!(read_bytes(b"not a diffraction pattern", "notes.txt").is_err());
+}
+
+#[test]
+fn test_33_read_with_format() {
+    // A GSAS title line with a stray NUL byte looks binary to the `.raw` sniffer.
+    let mut bytes = fs_read("tests/data/gsas_raw/gsas.raw").expect("Failed to read file");
+    let eol = bytes
+        .iter()
+        .position(|&b| b == b'\n')
+        .expect("No title line");
+    bytes.insert(eol, 0);
+    let pattern = read_with_format(Cursor::new(&bytes), Format::GsasRaw, &ReadOptions::new())
+        .expect("Failed to load GSAS raw");
+    assert!(!pattern.x.is_empty());
+    assert_eq!(pattern.x.len(), pattern.y.len());
+
+    let file = File::open("tests/data/xrdml/sample.xrdml").expect("Failed to open file");
+    let pattern =
+        read_with_format(file, Format::Xrdml, &ReadOptions::new()).expect("Failed to load XRDML");
+    assert!(pattern.metadata.wavelength.is_some());
+
+    let file = File::open("tests/data/xy/sample.xy").expect("Failed to open file");
+    assert!(read_with_format(file, Format::Rasx, &ReadOptions::new()).is_err());
+
+    let bytes = fs_read("tests/data/rasx/sample.rasx").expect("Failed to read file");
+    let raw = read_with_format(Cursor::new(&bytes), Format::Rasx, &ReadOptions::new())
+        .expect("Failed to load RASX");
+    assert!(raw.attenuation.is_some());
+    let options = ReadOptions::new().apply_attenuation(true);
+    let scaled =
+        read_with_format(Cursor::new(&bytes), Format::Rasx, &options).expect("Failed to load RASX");
+    assert!(scaled.attenuation.is_none());
+    let mut expected = raw.clone();
+    expected.apply_attenuation();
+    assert_eq!(scaled.y, expected.y);
+}
+
+/// Reads `STEPS <start> <step>` followed by intensities, one per line.
+struct StepsReader(&'static [&'static str]);
+
+impl PatternReader for StepsReader {
+    fn name(&self) -> &str {
+        "Steps"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.0
+    }
+
+    fn detect(&self, reader: &mut dyn ReadSeek) -> Result<Option<f64>, Error> {
+        let mut magic = [0u8; 5];
+        let matched = reader.read_exact(&mut magic).is_ok() && &magic == b"STEPS";
+        Ok(matched.then_some(1.0))
+    }
+
+    fn parse_all(
+        &self,
+        reader: &mut dyn ReadSeek,
+        _options: &ReadOptions,
+    ) -> Result<Vec<Pattern>, Error> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let body = text
+            .strip_prefix("STEPS")
+            .ok_or_else(|| Error::parse("Missing STEPS header"))?;
+        let values: Vec<f64> = body
+            .split_whitespace()
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| Error::parse(format!("Bad value {v}")))
+            })
+            .collect::<Result<_, _>>()?;
+        let (axis, y) = values.split_at(2.min(values.len()));
+        let x = (0..y.len()).map(|i| axis[0] + axis[1] * i as f64).collect();
+        Ok(vec![Pattern::new(x, y.to_vec(), None)?])
+    }
+}
+
+#[test]
+fn test_34_pattern_reader_registry() {
+    let steps = b"STEPS 10.0 0.5\n1\n2\n3\n";
+
+    // Built-in formats are registered through the same trait.
+    let mut registry = FormatRegistry::new();
+    assert_eq!(registry.names().len(), 6);
+    assert!(registry.read_bytes(steps, "scan.stp").is_err());
+    let xy = registry
+        .read("tests/data/xy/sample.xy")
+        .expect("Failed to load xy");
+    assert!(!xy.x.is_empty());
+
+    // Later readers take precedence, by extension and by content.
+    registry.register(StepsReader(&["stp", "xy"]));
+    assert_eq!(registry.names()[0], "Steps");
+    let pattern = registry
+        .read_bytes(steps, "scan.stp")
+        .expect("Failed to load custom format");
+    assert_eq!(pattern.x, vec![10.0, 10.5, 11.0]);
+    assert_eq!(pattern.y, vec![1.0, 2.0, 3.0]);
+    let pattern = registry
+        .read_bytes(steps, "scan.dat")
+        .expect("Failed to detect custom format");
+    assert_eq!(pattern.y.len(), 3);
+    let detected = registry
+        .detect(Cursor::new(steps))
+        .expect("Failed to detect")
+        .expect("No format detected");
+    assert_eq!(detected, ("Steps", 1.0));
+    // A claimed `.xy` file that the custom reader rejects falls back to the built-in.
+    let fallback = registry
+        .read("tests/data/xy/sample.xy")
+        .expect("Failed to fall back to xy");
+    assert_eq!(fallback.x, xy.x);
+    // When the fallback fails as well, the claimed reader's error is kept.
+    let columns = b"10.0 1\n10.5 2\nbad line\n";
+    let strict = ReadOptions::new().strict(true);
+    let err = registry
+        .read_reader_with(Cursor::new(columns), "scan.stp", &strict)
+        .expect_err("Strict fallback should fail");
+    assert!(err.to_string().contains("Missing STEPS header"), "{err}");
+
+    let empty = FormatRegistry::empty();
+    assert!(matches!(
+        empty.read_bytes(steps, "scan.stp"),
+        Err(Error::UnknownFormat)
+    ));
+
+    // The process-wide registry backs `geddes::read`.
+    assert!(read_bytes(steps, "scan.stp").is_err());
+    register_reader(StepsReader(&["stp"]));
+    let patterns = read_all_bytes(steps, "scan.stp").expect("Failed to load custom format");
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(patterns[0].x, vec![10.0, 10.5, 11.0]);
+}
+
+#[test]
+fn test_35_read_options() {
+    let defaults = ReadOptions::new();
+    assert_eq!(defaults, ReadOptions::default());
+
+    // GSAS CONST positions in degrees rather than centidegrees.
+    let gsas = read("tests/data/gsas_raw/gsas.raw").expect("Failed to load GSAS raw");
+    let options = ReadOptions::new().gsas_centidegrees(false);
+    let degrees =
+        read_with("tests/data/gsas_raw/gsas.raw", &options).expect("Failed to load GSAS raw");
+    assert_eq!(degrees.y, gsas.y);
+    assert!((degrees.x[0] - gsas.x[0] * 100.0).abs() < 1e-9);
+
+    // RASX data set selection.
+    let bytes = build_zip(&[
+        ("Data0/Profile0.txt", "10.0 1.0 1\n10.1 2.0 1\n"),
+        ("Data1/Profile1.txt", "20.0 3.0 1\n20.1 4.0 1\n20.2 5.0 1\n"),
+    ]);
+    let options = ReadOptions::new().rasx_data_set(1);
+    let second = read_bytes_with(&bytes, "multi.rasx", &options).expect("Failed to load rasx");
+    assert_eq!(second.x, vec![20.0, 20.1, 20.2]);
+    let all = read_all_bytes_with(&bytes, "multi.rasx", &options).expect("Failed to load rasx");
+    assert_eq!(all.len(), 2);
+    let options = ReadOptions::new().rasx_data_set(2);
+    assert!(matches!(
+        read_with_format(Cursor::new(&bytes), Format::Rasx, &options),
+        Err(Error::FileNotFoundInArchive(_))
+    ));
+
+    // XRDML axis selection.
+    let options = ReadOptions::new().xrdml_axis("Omega");
+    let omega = read_with("tests/data/xrdml/sample.xrdml", &options).expect("Failed to load XRDML");
+    assert!((omega.x[0] - 2.00328257).abs() < 1e-9);
+    assert!((omega.x[omega.x.len() - 1] - 34.99968294).abs() < 1e-9);
+    let options = ReadOptions::new().xrdml_axis("Chi");
+    let err = read_with_format(
+        File::open("tests/data/xrdml/sample.xrdml").expect("Failed to open file"),
+        Format::Xrdml,
+        &options,
+    )
+    .expect_err("Chi has no positions");
+    assert!(err.to_string().contains("Chi"));
+
+    // XY and CSV comment prefixes.
+    let data = b"% 5.0 6.0\n; note\n10.0 1.0\n11.0 2.0\n";
+    let options = ReadOptions::new().comment_prefixes(["%", ";"]);
+    let pattern = read_bytes_with(data, "data.xy", &options).expect("Failed to load xy");
+    assert_eq!(pattern.x, vec![10.0, 11.0]);
+    let pattern = read_bytes_with(data, "data.csv", &options).expect("Failed to load csv");
+    assert_eq!(pattern.y, vec![1.0, 2.0]);
+}
+
+#[test]
+fn test_36_strict_mode() {
+    let strict = ReadOptions::new().strict(true);
+    let location = |data: &[u8], name: &str| match read_bytes_with(data, name, &strict) {
+        Err(Error::Parse {
+            location: Some(location),
+            ..
+        }) => location,
+        other => panic!("Expected a located parse error, got {other:?}"),
+    };
+
+    // A corrupted line is skipped by default and reported in strict mode.
+    let data = b"# header\n10.0 1.0\n10.1 x2.0\n10.2 3.0\n";
+    let pattern = read_bytes(data, "data.xy").expect("Failed to load xy");
+    assert_eq!(pattern.x, vec![10.0, 10.2]);
+    assert_eq!(
+        location(data, "data.xy"),
+        Location {
+            line: 3,
+            offset: 18,
+            text: "10.1 x2.0".to_string(),
+        }
+    );
+
+    // Error columns must be present on every line, and valid.
+    let data = b"10.0 1.0 0.1\r\n10.1 2.0\r\n";
+    let pattern = read_bytes(data, "data.xy").expect("Failed to load xy");
+    assert!(pattern.e.is_none());
+    let found = location(data, "data.xy");
+    assert_eq!((found.line, found.offset), (2, 14));
+    let err = read_bytes_with(data, "data.xy", &strict).unwrap_err();
+    assert!(err.to_string().contains("expected 3 columns, found 2"));
+    assert!(err.to_string().contains("line 2 (byte 14)"));
+    assert_eq!(location(b"1,2,a\n", "data.csv").line, 1);
+
+    // Valid files load the same in both modes.
+    for path in ["tests/data/xy/sample.xy", "tests/data/csv/sample.csv"] {
+        let lenient = read(path).expect("Failed to load file");
+        let checked = read_with(path, &strict).expect("Failed to load file in strict mode");
+        assert_eq!(checked.x, lenient.x);
+        assert_eq!(checked.e, lenient.e);
+    }
+    let options = strict.clone().comment_prefixes(["%"]);
+    let pattern =
+        read_bytes_with(b"% sample\n1 2\n", "data.xy", &options).expect("Failed to load xy");
+    assert_eq!(pattern.y, vec![2.0]);
+}
+
+#[test]
+fn test_37_read_report_warnings() {
+    let options = ReadOptions::new();
+    let data = b"10.0 1.0 0.1\n10.1 ??\n10.2 2.0\n10.3 3.0\n10.4 n/a\n";
+    let report = read_report_bytes(data, "data.xy", &options).expect("Failed to load xy");
+    assert_eq!(report.pattern.x, vec![10.0, 10.2, 10.3]);
+    assert_eq!(report.warnings.len(), 2);
+    let skipped = &report.warnings[0];
+    assert_eq!(skipped.kind, WarningKind::SkippedLine);
+    assert_eq!(skipped.count, 2);
+    let lines: Vec<usize> = skipped.locations.iter().map(|l| l.line).collect();
+    assert_eq!(lines, vec![2, 5]);
+    assert_eq!(skipped.locations[0].text, "10.1 ??");
+    let columns = &report.warnings[1];
+    assert_eq!(columns.kind, WarningKind::InconsistentColumns);
+    assert_eq!(columns.count, 2);
+    assert_eq!(columns.message, "expected 3 columns, found 2");
+    assert_eq!(columns.locations[0].line, 3);
+
+    // RASX profiles written with a byte order mark.
+    let bytes = build_zip(&[("Data0/Profile0.txt", "\u{feff}10.0 1.0 1\n10.1 2.0 1\n")]);
+    let report = read_report_bytes(&bytes, "bom.rasx", &options).expect("Failed to load rasx");
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|w| w.kind).collect();
+    assert!(kinds.contains(&WarningKind::ByteOrderMark));
+
+    // XRDML intensities that are not numbers are kept as NaN.
+    let xml = r#"<xrdMeasurements><xrdMeasurement><scan><dataPoints>
+<positions axis="2Theta" unit="deg"><startPosition>10</startPosition><endPosition>11</endPosition></positions>
+<intensities unit="counts">1 x 3</intensities>
+</dataPoints></scan></xrdMeasurement></xrdMeasurements>"#;
+    let report = read_report_bytes(xml, "bad.xrdml", &options).expect("Failed to load xrdml");
+    assert_eq!(report.pattern.x, vec![10.0, 10.5, 11.0]);
+    assert!(report.pattern.y[1].is_nan());
+    assert_eq!(report.warnings[0].kind, WarningKind::InvalidValue);
+    assert_eq!(report.warnings[0].locations[0].text, "x");
+
+    // Clean files report nothing.
+    for path in [
+        "tests/data/xy/sample.xy",
+        "tests/data/csv/sample.csv",
+        "tests/data/xrdml/sample.xrdml",
+        "tests/data/gsas_raw/gsas.raw",
+    ] {
+        let report = read_report(path, &options).expect("Failed to load file");
+        assert!(report.warnings.is_empty(), "{path}: {:?}", report.warnings);
+    }
+}
+
+#[test]
+fn test_38_text_encodings() {
+    let utf16 = |text: &str, little_endian: bool, bom: bool| -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for unit in bom.then_some(0xfeff).into_iter().chain(text.encode_utf16()) {
+            if little_endian {
+                bytes.extend(unit.to_le_bytes());
+            } else {
+                bytes.extend(unit.to_be_bytes());
+            }
+        }
+        bytes
+    };
+    let text = "# 2theta intensity\n10.0 1.0\n10.1 2.0\n";
+
+    // A UTF-8 byte order mark no longer costs the first point.
+    let bytes = [b"\xef\xbb\xbf".as_slice(), b"10.0 1.0\n10.1 2.0\n"].concat();
+    let pattern = read_bytes(&bytes, "bom.xy").expect("Failed to load xy");
+    assert_eq!(pattern.x, vec![10.0, 10.1]);
+    let zip = build_zip(&[("Data0/Profile0.txt", "\u{feff}10.0 1.0 1\n10.1 2.0 1\n")]);
+    let report =
+        read_report_bytes(&zip, "bom.rasx", &ReadOptions::new()).expect("Failed to load rasx");
+    assert_eq!(report.pattern.x, vec![10.0, 10.1]);
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].kind, WarningKind::ByteOrderMark);
+
+    // UTF-16 with and without a byte order mark, also found by content.
+    for (little_endian, bom) in [(true, true), (false, true), (true, false), (false, false)] {
+        let bytes = utf16(text, little_endian, bom);
+        let pattern = read_bytes(&bytes, "wide.xy").expect("Failed to load UTF-16 xy");
+        assert_eq!(pattern.x, vec![10.0, 10.1]);
+        let detection = detect_format(&mut Cursor::new(&bytes)).unwrap();
+        assert_eq!(detection.map(|d| d.format), Some(Format::Xy));
+    }
+    let csv = utf16("x,y\n1.5,2.5\n", true, true);
+    let pattern = read_bytes(&csv, "wide.csv").expect("Failed to load UTF-16 csv");
+    assert_eq!(pattern.y, vec![2.5]);
+
+    // Latin-1 comments no longer fail the read.
+    let pattern = read_bytes(b"# 2\xb0 theta\n20.0 5.0\n", "latin1.xy").expect("Failed to load xy");
+    assert_eq!(pattern.x, vec![20.0]);
+
+    // A UTF-16 GSAS file is still told apart from Bruker binary RAW.
+    let original = read("tests/data/gsas_raw/gsas.raw").expect("Failed to load GSAS raw");
+    let text = String::from_utf8(fs_read("tests/data/gsas_raw/gsas.raw").unwrap()).unwrap();
+    let pattern =
+        read_bytes(utf16(&text, true, true), "wide.raw").expect("Failed to load UTF-16 GSAS");
+    assert_eq!(pattern.x, original.x);
+    assert_eq!(pattern.y, original.y);
+    assert_eq!(pattern.metadata.title, original.metadata.title);
+}
+
+#[test]
+fn test_39_xrdml_list_positions() {
+    let xml = |positions: &str, counts: &str| {
+        format!(
+            r#"<xrdMeasurements><xrdMeasurement><scan scanAxis="2Theta"><dataPoints>
+<positions axis="2Theta" unit="deg">{positions}</positions>
+<positions axis="Omega" unit="deg"><commonPosition>5</commonPosition></positions>
+<intensities unit="counts">{counts}</intensities>
+</dataPoints></scan></xrdMeasurement></xrdMeasurements>"#
+        )
+    };
+
+    let list = "<listPositions>10.0 10.5 11.5 13.5</listPositions>";
+    let pattern = read_bytes(xml(list, "1 2 3 4"), "list.xrdml").expect("Failed to load xrdml");
+    assert_eq!(pattern.x, vec![10.0, 10.5, 11.5, 13.5]);
+    assert_eq!(pattern.y, vec![1.0, 2.0, 3.0, 4.0]);
+
+    let err = read_bytes(xml(list, "1 2 3"), "list.xrdml").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("4 2Theta list positions for 3 intensities"));
+    let bad = "<listPositions>10.0 x</listPositions>";
+    assert!(read_bytes(xml(bad, "1 2"), "list.xrdml").is_err());
+
+    // Start and end positions still give an evenly spaced axis.
+    let range = "<startPosition>10</startPosition><endPosition>11</endPosition>";
+    let pattern = read_bytes(xml(range, "1 2 3"), "range.xrdml").expect("Failed to load xrdml");
+    assert_eq!(pattern.x, vec![10.0, 10.5, 11.0]);
+}
+
+#[test]
+fn test_40_xrdml_scan_axis() {
+    let xml = |scan_axis: &str| {
+        format!(
+            r#"<xrdMeasurements><xrdMeasurement><scan scanAxis="{scan_axis}"><dataPoints>
+<positions axis="2Theta" unit="deg"><startPosition>60</startPosition><endPosition>70</endPosition></positions>
+<positions axis="Omega" unit="deg"><startPosition>30</startPosition><endPosition>35</endPosition></positions>
+<positions axis="Phi" unit="deg"><startPosition>0</startPosition><endPosition>360</endPosition></positions>
+<intensities unit="counts">1 2 3</intensities>
+</dataPoints></scan></xrdMeasurement></xrdMeasurements>"#
+        )
+    };
+    let cases = [
+        ("Gonio", "2Theta", 60.0),
+        ("2Theta-Omega", "2Theta", 60.0),
+        ("Omega-2Theta", "Omega", 30.0),
+        ("Omega", "Omega", 30.0),
+        ("Phi", "Phi", 0.0),
+    ];
+    for (scan_axis, x_axis, first) in cases {
+        let pattern = read_bytes(xml(scan_axis), "scan.xrdml").expect("Failed to load xrdml");
+        assert_eq!(pattern.x[0], first, "{scan_axis}");
+        let scan = pattern.metadata.scan.expect("Missing scan info");
+        assert_eq!(scan.axis.as_deref(), Some(scan_axis));
+        assert_eq!(scan.x_axis.as_deref(), Some(x_axis));
+        assert_eq!(scan.position_unit.as_deref(), Some("deg"));
+    }
+
+    // An explicit axis overrides scanAxis.
+    let options = ReadOptions::new().xrdml_axis("2Theta");
+    let pattern =
+        read_bytes_with(xml("Omega-2Theta"), "scan.xrdml", &options).expect("Failed to load xrdml");
+    assert_eq!(pattern.x, vec![60.0, 65.0, 70.0]);
+    let scan = pattern.metadata.scan.expect("Missing scan info");
+    assert_eq!(scan.x_axis.as_deref(), Some("2Theta"));
+
+    let pattern = read("tests/data/xrdml/sample.xrdml").expect("Failed to load xrdml file");
+    let scan = pattern.metadata.scan.expect("Missing scan info");
+    assert_eq!(scan.x_axis.as_deref(), Some("2Theta"));
+}
+
+#[test]
+fn test_41_xrdml_counts_and_counting_time() {
+    let xml = |data: &str| {
+        format!(
+            r#"<xrdMeasurements><xrdMeasurement><scan scanAxis="Gonio"><dataPoints>
+<positions axis="2Theta" unit="deg"><startPosition>10</startPosition><endPosition>11</endPosition></positions>
+{data}
+</dataPoints></scan></xrdMeasurement></xrdMeasurements>"#
+        )
+    };
+    let cps = ReadOptions::new().xrdml_counts_per_second(true);
+
+    // Newer files store <counts>; e is the Poisson error of the counts.
+    let counts = xml(r#"<commonCountingTime unit="seconds">4</commonCountingTime>
+<counts unit="counts">16 25 0</counts>"#);
+    let pattern = read_bytes(&counts, "counts.xrdml").expect("Failed to load xrdml");
+    assert_eq!(pattern.y, vec![16.0, 25.0, 0.0]);
+    assert_eq!(pattern.e, Some(vec![4.0, 5.0, 0.0]));
+    assert_eq!(pattern.metadata.counting_time, Some(4.0));
+    let scan = pattern.metadata.scan.expect("Missing scan info");
+    assert_eq!(scan.intensity_unit.as_deref(), Some("counts"));
+
+    let pattern = read_bytes_with(&counts, "counts.xrdml", &cps).expect("Failed to load xrdml");
+    assert_eq!(pattern.y, vec![4.0, 6.25, 0.0]);
+    assert_eq!(pattern.e, Some(vec![1.0, 1.25, 0.0]));
+    let scan = pattern.metadata.scan.expect("Missing scan info");
+    assert_eq!(scan.intensity_unit.as_deref(), Some("cps"));
+
+    // cps data keep their unit; errors still come from the raw counts.
+    let rates = xml(r#"<commonCountingTime unit="seconds">4</commonCountingTime>
+<intensities unit="cps">4 1</intensities>"#);
+    let pattern = read_bytes_with(&rates, "cps.xrdml", &cps).expect("Failed to load xrdml");
+    assert_eq!(pattern.y, vec![4.0, 1.0]);
+    assert_eq!(pattern.e, Some(vec![1.0, 0.5]));
+
+    // Without a counting time there is nothing to divide by.
+    let untimed = xml(r#"<intensities unit="counts">1 2</intensities>"#);
+    let err = read_bytes_with(&untimed, "untimed.xrdml", &cps).unwrap_err();
+    assert!(err.to_string().contains("counting time"));
+
+    let sample = read("tests/data/xrdml/sample.xrdml").expect("Failed to load xrdml file");
+    let rate = read_with("tests/data/xrdml/sample.xrdml", &cps).expect("Failed to load xrdml");
+    assert!((rate.y[0] - sample.y[0] / 39.27).abs() < 1e-9);
+    assert_eq!(sample.e.map(|e| e[0]), Some(1305f64.sqrt()));
+}
+
+#[test]
+fn test_42_xrdml_beam_attenuation() {
+    let xml = |factors: &str| {
+        format!(
+            r#"<xrdMeasurements><xrdMeasurement><scan scanAxis="Gonio"><dataPoints>
+<positions axis="2Theta" unit="deg"><startPosition>10</startPosition><endPosition>11</endPosition></positions>
+<commonCountingTime unit="seconds">1</commonCountingTime>
+<beamAttenuationFactors>{factors}</beamAttenuationFactors>
+<intensities unit="counts">100 400 100</intensities>
+</dataPoints></scan></xrdMeasurement></xrdMeasurements>"#
+        )
+    };
+    let data = xml("1 10.5 1");
+
+    // Raw counts and factors are kept apart by default.
+    let pattern = read_bytes(&data, "att.xrdml").expect("Failed to load xrdml");
+    assert_eq!(pattern.y, vec![100.0, 400.0, 100.0]);
+    assert_eq!(pattern.e, Some(vec![10.0, 20.0, 10.0]));
+    assert_eq!(pattern.attenuation, Some(vec![1.0, 10.5, 1.0]));
+
+    let options = ReadOptions::new().apply_attenuation(true);
+    let pattern = read_bytes_with(&data, "att.xrdml", &options).expect("Failed to load xrdml");
+    assert_eq!(pattern.y, vec![100.0, 4200.0, 100.0]);
+    assert_eq!(pattern.e, Some(vec![10.0, 210.0, 10.0]));
+    assert!(pattern.attenuation.is_none());
+
+    let err = read_bytes(xml("1 2"), "att.xrdml").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("2 beam attenuation factors for 3 intensities"));
+    assert!(read_bytes(xml("1 x 1"), "att.xrdml").is_err());
+
+    let sample = read("tests/data/xrdml/sample.xrdml").expect("Failed to load xrdml file");
+    assert!(sample.attenuation.is_none());
+}
+
+#[test]
+fn test_43_xrdml_reciprocal_space_map() {
+    let scan = |axis: &str, omega: &str, two_theta: &str, intensities: &str| {
+        format!(
+            r#"<scan scanAxis="{axis}"><dataPoints>
+<positions axis="2Theta" unit="deg">{two_theta}</positions>
+<positions axis="Omega" unit="deg">{omega}</positions>
+<commonCountingTime unit="seconds">1</commonCountingTime>
+<intensities unit="counts">{intensities}</intensities>
+</dataPoints></scan>"#
+        )
+    };
+    let range = |start: f64, end: f64| {
+        format!("<startPosition>{start}</startPosition><endPosition>{end}</endPosition>")
+    };
+    let xml = |wavelength: &str, scans: Vec<String>| {
+        format!(
+            "<xrdMeasurements><xrdMeasurement>{wavelength}{}</xrdMeasurement></xrdMeasurements>",
+            scans.concat()
+        )
+    };
+    let wavelength = r#"<usedWavelength intended="K-Alpha 1"><kAlpha1 unit="Angstrom">1.5406</kAlpha1></usedWavelength>"#;
+
+    // Omega-2Theta scans stepped in Omega.
+    let scans: Vec<String> = [-1.0, 0.0, 1.0]
+        .iter()
+        .map(|offset| {
+            let omega = range(30.0 + offset, 31.0 + offset);
+            scan("Omega-2Theta", &omega, &range(60.0, 62.0), "1 4 9")
+        })
+        .collect();
+    let data = xml(wavelength, scans.clone());
+    let map = read_xrdml_map_bytes(&data, &ReadOptions::new()).expect("Failed to load map");
+    assert_eq!(map.scan_axis, "Omega-2Theta");
+    assert_eq!(map.step_axis, "Omega");
+    assert_eq!(map.step_positions, vec![29.0, 30.0, 31.0]);
+    assert_eq!(map.omega[0], vec![29.0, 29.5, 30.0]);
+    assert_eq!(map.two_theta[2], vec![60.0, 61.0, 62.0]);
+    assert_eq!(map.intensities, vec![vec![1.0, 4.0, 9.0]; 3]);
+    assert!(map.attenuation.is_none());
+
+    let lambda = map.metadata.wavelength.expect("Wavelength not read");
+    let (qx, qz) = map.to_q().expect("Failed to convert to Q");
+    assert_eq!((qx.len(), qx[1].len()), (3, 3));
+    // The middle scan is symmetric: qx = 0 and qz = 4π sin θ / λ.
+    let expected = 4.0 * std::f64::consts::PI * 30.5_f64.to_radians().sin() / lambda;
+    assert!(qx[1].iter().all(|q| q.abs() < 1e-12));
+    assert!((qz[1][1] - expected).abs() < 1e-12);
+    assert!(qx[0][1] > 0.0 && qx[2][1] < 0.0);
+    let (qx_long, _) = map.to_q_with_wavelength(2.0 * lambda);
+    assert!((qx_long[0][1] * 2.0 - qx[0][1]).abs() < 1e-12);
+
+    let cps = ReadOptions::new().xrdml_counts_per_second(true);
+    assert!(read_xrdml_map_bytes(&data, &cps).is_ok());
+
+    // Rocking curves stepped in 2Theta.
+    let scans: Vec<String> = [40.0, 41.0]
+        .iter()
+        .map(|two_theta| {
+            let common = format!("<commonPosition>{two_theta}</commonPosition>");
+            scan("Omega", &range(19.0, 21.0), &common, "5 6 7")
+        })
+        .collect();
+    let map =
+        read_xrdml_map_bytes(xml("", scans), &ReadOptions::new()).expect("Failed to load map");
+    assert_eq!(map.step_axis, "2Theta");
+    assert_eq!(map.step_positions, vec![40.0, 41.0]);
+    assert_eq!(map.two_theta[1], vec![41.0; 3]);
+    let err = map.to_q().unwrap_err();
+    assert!(err.to_string().contains("Wavelength not found"));
+
+    let gonio = xml(
+        wavelength,
+        vec![format!(
+            r#"<scan scanAxis="Gonio"><dataPoints><positions axis="2Theta" unit="deg">{}</positions>
+<intensities unit="counts">1 2 3</intensities></dataPoints></scan>"#,
+            range(10.0, 12.0)
+        )],
+    );
+    let err = read_xrdml_map_bytes(gonio, &ReadOptions::new()).unwrap_err();
+    assert!(err.to_string().contains("XRDML missing Omega positions"));
+
+    let sample = read_xrdml_map("tests/data/xrdml/sample.xrdml", &ReadOptions::new())
+        .expect("Failed to load xrdml file");
+    assert_eq!(sample.intensities.len(), 1);
+}
+
+#[test]
+fn test_44_content_overrides_wrong_extension() {
+    let data = fs_read("tests/data/gsas_raw/gsas.raw").expect("Failed to read raw file");
+    let expected = read_bytes(&data, "gsas.raw").expect("Failed to load raw file");
+
+    for name in ["gsas.xy", "gsas.csv", "gsas.xrdml"] {
+        let pattern = read_bytes(&data, name).expect("Failed to load misnamed file");
+        assert_eq!(pattern.x, expected.x, "{name}");
+        assert_eq!(pattern.y, expected.y, "{name}");
+        assert_eq!(pattern.e, expected.e, "{name}");
+        let patterns = read_all_bytes(&data, name).expect("Failed to load misnamed file");
+        assert_eq!(
+            patterns.len(),
+            read_all_bytes(&data, "gsas.raw").unwrap().len()
+        );
+    }
+
+    // Plain columns are not conclusive, so the extension still decides.
+    let csv = read_bytes(b"10.0,100.0\n10.1,105.0\n", "scan.csv").expect("Failed to load csv");
+    assert_eq!(csv.y, vec![100.0, 105.0]);
+}
+
+#[test]
+fn test_45_gsas_blank_fixed_width_fields() {
+    // Blank fields inside a record read as 0 and keep later fields in place.
+    let esd = format!(
+        "{:>8}{:>8}{:>8}{:>8}{:>8}{:>8}",
+        "100", "", "400", "20", "900", "30"
+    );
+    let alt = format!(
+        "{:>8}{:>7}{:>5}{:>8}{:>7}{:>5}",
+        "32000", "", "0.5", "32320", "6.5", "0.6"
+    );
+    let raw = |esd: &str| {
+        format!(
+            "\
+Blank fields
+BANK 1 3 1 CONST 1000.0 10.0 0 0 ESD
+{esd}
+BANK 2 2 1 RALF 32000 320 32000 0.01 ALT
+{alt}
+"
+        )
+    };
+
+    let patterns = read_all_bytes(raw(&esd), "blank.raw").expect("Failed to load gsas");
+    assert_eq!(patterns[0].y, vec![100.0, 400.0, 900.0]);
+    assert_eq!(patterns[0].e, Some(vec![0.0, 20.0, 30.0]));
+    assert_eq!(patterns[1].x, vec![1000.0, 1010.0]);
+    assert_eq!(patterns[1].y, vec![0.0, 6.5]);
+    assert_eq!(patterns[1].e, Some(vec![0.5, 0.6]));
+
+    // Invalid text keeps its place as NaN, or fails in strict mode.
+    let invalid = format!("{:>8}{:>8}{:>8}{:>8}", "100", "n/a", "400", "20");
+    let patterns = read_all_bytes(raw(&invalid), "blank.raw").expect("Failed to load gsas");
+    let e = patterns[0].e.as_ref().expect("Missing esd");
+    assert!(e[0].is_nan());
+    assert_eq!((patterns[0].y[1], e[1]), (400.0, 20.0));
+    let strict = ReadOptions::new().strict(true);
+    let err = read_all_bytes_with(raw(&invalid), "blank.raw", &strict).unwrap_err();
+    assert!(err.to_string().contains("invalid fixed-width field"));
+    assert!(err.to_string().contains("line 3"));
+}
+
+#[test]
+fn test_46_strict_mode_rasx_and_xrdml() {
+    let strict = ReadOptions::new().strict(true);
+
+    // RASX: malformed profile lines fail in strict mode, for one or all data sets.
+    let bytes = build_zip(&[
+        (
+            "Data0/Profile0.txt",
+            "% note\n10.0 1.0 1\n10.1 oops 1\n10.2 3.0 x\n",
+        ),
+        ("Data1/Profile1.txt", "20.0 1.0 1\n"),
+    ]);
+    let pattern = read_bytes(&bytes, "bad.rasx").expect("Failed to load rasx");
+    assert_eq!(pattern.x, vec![10.0, 10.2]);
+    assert_eq!(pattern.attenuation, Some(vec![1.0, 1.0]));
+    let report = read_report_bytes(&bytes, "bad.rasx", &ReadOptions::new()).unwrap();
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|w| w.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![WarningKind::SkippedLine, WarningKind::InvalidValue]
+    );
+
+    let options = strict.clone().comment_prefixes(["%"]);
+    for err in [
+        read_bytes_with(&bytes, "bad.rasx", &options).unwrap_err(),
+        read_all_bytes_with(&bytes, "bad.rasx", &options).unwrap_err(),
+    ] {
+        match err {
+            Error::Parse {
+                location: Some(location),
+                ..
+            } => assert_eq!((location.line, location.text.as_str()), (3, "10.1 oops 1")),
+            other => panic!("Expected a located parse error, got {other:?}"),
+        }
+    }
+    // Without the `%` prefix the note line is the first malformed one.
+    let err = read_all_bytes_with(&bytes, "bad.rasx", &strict).unwrap_err();
+    assert!(err.to_string().contains("line 1"));
+
+    // XRDML: a bad intensity keeps its place as NaN, or fails in strict mode.
+    let data = r#"<xrdMeasurements><xrdMeasurement><scan scanAxis="Gonio"><dataPoints>
+<positions axis="2Theta" unit="deg"><startPosition>10</startPosition><endPosition>13</endPosition></positions>
+<intensities unit="counts">1 4
+n/a 16</intensities>
+</dataPoints></scan></xrdMeasurement></xrdMeasurements>"#;
+    let report = read_report_bytes(data, "bad.xrdml", &ReadOptions::new()).unwrap();
+    assert_eq!(report.pattern.x, vec![10.0, 11.0, 12.0, 13.0]);
+    assert_eq!(report.pattern.y[3], 16.0);
+    assert!(report.pattern.y[2].is_nan());
+    assert_eq!(report.warnings[0].kind, WarningKind::InvalidValue);
+    assert_eq!(report.warnings[0].locations[0].line, 4);
+
+    let err = read_bytes_with(data, "bad.xrdml", &strict).unwrap_err();
+    let offset = data.find("n/a").unwrap() as u64;
+    match err {
+        Error::Parse {
+            location: Some(location),
+            ..
+        } => assert_eq!(
+            location,
+            Location {
+                line: 4,
+                offset,
+                text: "n/a".to_string(),
+            }
+        ),
+        other => panic!("Expected a located parse error, got {other:?}"),
+    }
+    read_with("tests/data/xrdml/sample.xrdml", &strict).expect("Failed to load xrdml file");
+}
+
+#[test]
+fn test_47_gsas_short_time_map() {
+    // The map ends at 2200 ticks, two channels short of the bank.
+    let raw = "\
+Short time map
+BANK 1 4 1 TIME_MAP 1 STD
+  1  2  3  4
+TIME_MAP 1 4 1 TIME_MAP 1000
+         1      2000       100      2200
+";
+    let err = read_bytes(raw, "short.raw").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("TIME_MAP 1 covers 2 of 4 channels"));
+}
+
+#[test]
+fn test_48_gsas_warnings_and_strict_mode() {
+    let raw = "\
+Corrupt banks
+BANK 1 3 3 SLOG 1000.0 1210.0 0.1 0 FXYE
+1000.0 1.0 0.1
+1100.0 oops 0.2
+1210.0 3.0
+BANK 2 3 1 CONST 1000.0 10.0 0 0 STD
+ 1 x 3
+";
+    let options = ReadOptions::new();
+    let report = read_report_bytes(raw, "corrupt.raw", &options).expect("Failed to load gsas");
+    assert_eq!(report.pattern.x, vec![1000.0]);
+    let skipped = &report.warnings[0];
+    assert_eq!(skipped.kind, WarningKind::SkippedLine);
+    assert_eq!(skipped.count, 2);
+    assert_eq!(skipped.locations[0].line, 4);
+    assert_eq!(skipped.locations[1].text, "1210.0 3.0");
+
+    let patterns = read_all_bytes(raw, "corrupt.raw").expect("Failed to load gsas");
+    assert_eq!(patterns[1].x, vec![10.0, 10.1, 10.2]);
+    assert!(patterns[1].y[1].is_nan());
+
+    let strict = ReadOptions::new().strict(true);
+    let err = read_all_bytes_with(raw, "corrupt.raw", &strict).unwrap_err();
+    assert!(err.to_string().contains("line 4"));
+
+    // Only known data types are accepted after the coefficients.
+    let header = |tail: &str| format!("Header\nBANK 1 1 1 CONST 1000.0 10.0 0 0 {tail}\n 1\n");
+    let pattern = read_bytes(header("STD 5 extra"), "header.raw").expect("Failed to load gsas");
+    assert_eq!(
+        pattern.metadata.bank.expect("Missing bank").data_type,
+        "STD"
+    );
+    let err = read_bytes(header("COUNTS"), "header.raw").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Unsupported GSAS data type: COUNTS"));
+}
+
+#[test]
+fn test_49_rasx_broken_metadata() {
+    let bytes = build_zip(&[
+        ("Data0/Profile0.txt", "10.0 1.0 1\n10.1 2.0 1\n"),
+        (
+            "Data0/MesurementConditions0.xml",
+            "<MeasurementConditions><GeneralInformation></Operator>",
+        ),
+        ("root.xml", "<Info></Root>"),
+    ]);
+    let report = read_report_bytes(&bytes, "broken.rasx", &ReadOptions::new())
+        .expect("Broken metadata should not fail the load");
+    assert_eq!(report.pattern.y, vec![1.0, 2.0]);
+    assert!(report.pattern.metadata.operator.is_none());
+    let warning = &report.warnings[0];
+    assert_eq!(warning.kind, WarningKind::InvalidMetadata);
+    assert_eq!(warning.count, 2);
+    assert!(warning
+        .message
+        .starts_with("Data0/MesurementConditions0.xml skipped"));
+
+    let strict = ReadOptions::new().strict(true);
+    let pattern = read_bytes_with(&bytes, "broken.rasx", &strict).expect("Failed to load rasx");
+    assert_eq!(pattern.x, vec![10.0, 10.1]);
+}
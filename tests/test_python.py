@@ -1,6 +1,7 @@
 from pathlib import Path
 
 import geddes
+import pytest
 
 ROOT = Path(__file__).resolve().parents[1]
 DATA_DIR = ROOT / "tests" / "data"
@@ -87,3 +88,133 @@ def test_10_read_bytes_csv():
     data = path.read_bytes()
     pattern = geddes.read_bytes(data, "sample.csv")
     _assert_pattern(pattern)
+
+
+def test_11_read_xrdml_metadata():
+    """Expose instrument metadata on the loaded pattern."""
+    path = DATA_DIR / "xrdml" / "sample.xrdml"
+    pattern = geddes.read(str(path))
+    assert pattern.metadata.anode == "Cu"
+    assert pattern.metadata.wavelength == 1.540598
+    assert isinstance(pattern.metadata.extra, dict)
+
+
+def test_12_rasx_attenuation():
+    """Keep RASX attenuation factors and apply them on request."""
+    path = DATA_DIR / "rasx" / "sample.rasx"
+    pattern = geddes.read(str(path))
+    assert pattern.attenuation is not None
+    assert len(pattern.attenuation) == len(pattern.y)
+    pattern.apply_attenuation()
+    assert pattern.attenuation is None
+
+
+def test_13_read_all_rasx():
+    """Load every data set of a RASX archive."""
+    path = DATA_DIR / "rasx" / "sample.rasx"
+    patterns = geddes.read_all(str(path))
+    assert len(patterns) == 1
+    _assert_pattern(patterns[0])
+    patterns = geddes.read_all_bytes(path.read_bytes(), "sample.rasx")
+    assert len(patterns) == 1
+
+
+def test_14_diagnose_bruker_raw():
+    """Report how a Bruker RAW data block was located."""
+    path = DATA_DIR / "bruker_raw" / "bruker4_diffrac_eva.raw"
+    report = geddes.diagnose_bruker_raw(str(path))
+    assert report.version == "4"
+    assert not report.heuristic
+    assert report.confidence == 1.0
+    assert report.selected.layout == "structured"
+    report = geddes.diagnose_bruker_raw_bytes(path.read_bytes())
+    assert report.selected.count == 7134
+
+
+def test_15_read_with_format():
+    """Load a file with an explicitly chosen format."""
+    path = DATA_DIR / "xrdml" / "sample.xrdml"
+    pattern = geddes.read_with_format(str(path), geddes.Format.Xrdml)
+    _assert_pattern(pattern)
+    pattern = geddes.read_bytes_with_format(path.read_bytes(), geddes.Format.Xrdml)
+    _assert_pattern(pattern)
+
+
+def test_16_read_options():
+    """Pass parser options as keyword arguments."""
+    path = DATA_DIR / "xrdml" / "sample.xrdml"
+    omega = geddes.read(str(path), xrdml_axis="Omega")
+    assert abs(omega.x[0] - 2.00328257) < 1e-9
+    path = DATA_DIR / "gsas_raw" / "gsas.raw"
+    default = geddes.read(str(path))
+    degrees = geddes.read_bytes(path.read_bytes(), "gsas.raw", gsas_centidegrees=False)
+    assert abs(degrees.x[0] - default.x[0] * 100.0) < 1e-9
+    path = DATA_DIR / "rasx" / "sample.rasx"
+    pattern = geddes.read(str(path), apply_attenuation=True, rasx_data_set=0)
+    assert pattern.attenuation is None
+    pattern = geddes.read_bytes(b"% 1 2\n10 1\n11 2\n", "data.xy", comment_prefixes=["%"])
+    assert pattern.x == [10.0, 11.0]
+    with pytest.raises(ValueError):
+        geddes.read(str(path), rasx_data_set=5)
+
+
+def test_17_strict_mode():
+    """Report the line of a malformed data row in strict mode."""
+    data = b"10.0 1.0\n10.1 ???\n10.2 3.0\n"
+    pattern = geddes.read_bytes(data, "data.xy")
+    assert pattern.x == [10.0, 10.2]
+    with pytest.raises(ValueError, match="line 2"):
+        geddes.read_bytes(data, "data.xy", strict=True)
+    with pytest.raises(TypeError):
+        geddes.read_bytes(data, "data.xy", stric=True)
+
+
+def test_18_read_report():
+    """Report skipped lines instead of dropping them silently."""
+    data = b"10.0 1.0\n10.1 ???\n10.2 3.0\n"
+    report = geddes.read_bytes_report(data, "data.xy")
+    assert report.pattern.x == [10.0, 10.2]
+    assert len(report.warnings) == 1
+    warning = report.warnings[0]
+    assert warning.kind == geddes.WarningKind.SkippedLine
+    assert warning.count == 1
+    assert warning.locations[0].line == 2
+    assert warning.locations[0].text == "10.1 ???"
+    report = geddes.read_report(str(DATA_DIR / "xy" / "sample.xy"))
+    assert report.warnings == []
+
+
+def test_19_xrdml_scan_axis():
+    """Take x from the axis named by the XRDML scanAxis."""
+    path = DATA_DIR / "xrdml" / "sample.xrdml"
+    pattern = geddes.read(str(path))
+    assert pattern.metadata.scan.axis == "Gonio"
+    assert pattern.metadata.scan.x_axis == "2Theta"
+    omega = geddes.read(str(path), xrdml_axis="Omega")
+    assert omega.metadata.scan.x_axis == "Omega"
+
+
+def test_20_xrdml_counts_per_second():
+    """Convert XRDML counts to counts per second with Poisson errors."""
+    path = DATA_DIR / "xrdml" / "sample.xrdml"
+    counts = geddes.read(str(path))
+    assert abs(counts.e[0] - counts.y[0] ** 0.5) < 1e-9
+    cps = geddes.read(str(path), xrdml_counts_per_second=True)
+    time = counts.metadata.counting_time
+    assert abs(cps.y[0] - counts.y[0] / time) < 1e-9
+    assert cps.metadata.scan.intensity_unit == "cps"
+
+
+def test_21_read_xrdml_map():
+    """Load an XRDML file as a reciprocal space map and convert it to Q."""
+    path = DATA_DIR / "xrdml" / "sample.xrdml"
+    rsm = geddes.read_xrdml_map(str(path))
+    assert rsm.step_axis in ("Omega", "2Theta")
+    assert len(rsm.omega) == len(rsm.two_theta) == len(rsm.intensities) == 1
+    assert len(rsm.omega[0]) == len(rsm.intensities[0])
+    qx, qz = rsm.to_q()
+    assert len(qx[0]) == len(qz[0]) == len(rsm.intensities[0])
+    qx_long, _ = rsm.to_q_with_wavelength(2 * rsm.metadata.wavelength)
+    assert abs(qx_long[0][0] * 2 - qx[0][0]) < 1e-9
+    same = geddes.read_xrdml_map_bytes(path.read_bytes())
+    assert same.intensities == rsm.intensities